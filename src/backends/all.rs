@@ -1,8 +1,13 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 
 use crate::prelude::*;
+use crate::unknown_keys::{struct_fields, KeyChecker};
+use color_eyre::eyre::{eyre, Context};
 use color_eyre::Result;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use toml_edit::TableLike;
 
 macro_rules! append {
     ($($backend:ident),*) => {
@@ -41,6 +46,14 @@ macro_rules! any {
             $($backend,)*
         }
         impl AnyBackend {
+            pub const ALL: &'static [AnyBackend] = &[$(AnyBackend::$backend,)*];
+
+            pub fn is_available(&self, config: &Config) -> bool {
+                is_enabled(*self, config) && match self {
+                    $( AnyBackend::$backend => $backend::is_available(config), )*
+                }
+            }
+
             pub fn remove_packages(&self, packages: &BTreeSet<String>, no_confirm: bool, config: &Config) -> Result<()> {
                 match self {
                     $( AnyBackend::$backend => $backend::remove_packages(packages, no_confirm, config), )*
@@ -58,6 +71,14 @@ impl RawPackageIds {
     pub fn contains(&self, backend: AnyBackend, package: &String) -> bool {
         self.get(&backend).is_some_and(|x| x.contains(package))
     }
+
+    pub fn to_package_ids(&self) -> PackageIds {
+        PackageIds(
+            self.iter()
+                .map(|(backend, packages)| (*backend, packages.iter().cloned().collect()))
+                .collect(),
+        )
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, derive_more::Deref, derive_more::DerefMut)]
@@ -99,6 +120,40 @@ impl PackageIds {
         output
     }
 
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut output = Self::default();
+        for (backend, packages) in self.iter() {
+            if let Some(other_packages) = other.get(backend) {
+                output.0.insert(
+                    *backend,
+                    packages.intersection(other_packages).cloned().collect(),
+                );
+            }
+        }
+        output
+    }
+
+    /// Removes every package which matches any of the glob patterns for its
+    /// backend.
+    pub fn remove_matching(&mut self, patterns: &Self) -> Result<()> {
+        for (backend, patterns) in patterns.iter() {
+            let patterns = patterns
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(pattern).wrap_err(eyre!(
+                        "parsing the glob pattern {pattern:?} for the {backend} backend"
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Some(packages) = self.get_mut(backend) {
+                packages.retain(|package| !patterns.iter().any(|x| x.matches(package)));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn remove_packages(&self, no_confirm: bool, config: &Config) -> Result<()> {
         for (backend, packages) in self.0.iter() {
             if is_enabled(*backend, config) {
@@ -123,26 +178,6 @@ impl std::fmt::Display for PackageIds {
         Ok(())
     }
 }
-macro_rules! package_ids {
-    ($($backend:ident),*) => {
-        impl PackageIds {
-            pub fn to_install_options(self) -> InstallOptions {
-                InstallOptions {
-                    $(
-                        $backend: if let Some(packages) = self.get(&AnyBackend::$backend) {
-                            packages.iter().map(|x| (x.clone(), <$backend as Backend>::InstallOptions::default())).collect()
-                        } else {
-                            Default::default()
-                        },
-                    )*
-                }
-            }
-
-        }
-    }
-}
-apply_public_backends!(package_ids);
-
 macro_rules! query_infos {
     ($($backend:ident),*) => {
         #[derive(Debug, Clone, Default)]
@@ -222,6 +257,21 @@ macro_rules! install_options {
                 Ok(self)
             }
 
+            /// The packages, with their install options, which are in the given
+            /// package ids.
+            pub fn filter_package_ids(&self, package_ids: &PackageIds) -> Self {
+                Self {
+                    $(
+                        $backend: self
+                            .$backend
+                            .iter()
+                            .filter(|(package, _)| package_ids.contains(AnyBackend::$backend, package))
+                            .map(|(package, options)| (package.clone(), options.clone()))
+                            .collect(),
+                    )*
+                }
+            }
+
             pub fn install_packages(self, no_confirm: bool, config: &Config) -> Result<()> {
                 $(
                     if is_enabled(AnyBackend::$backend, config) {
@@ -236,9 +286,116 @@ macro_rules! install_options {
 }
 apply_public_backends!(install_options);
 
+macro_rules! backend_configs {
+    ($($backend:ident),*) => {
+        #[derive(Debug, Clone, Default)]
+        #[allow(non_snake_case)]
+        pub struct BackendConfigs {
+            $(
+                pub $backend: <$backend as Backend>::Config,
+            )*
+        }
+        impl BackendConfigs {
+            pub fn is_enabled(&self, backend: AnyBackend) -> bool {
+                match backend {
+                    $( AnyBackend::$backend => self.$backend.enabled, )*
+                }
+            }
+
+            /// The glob patterns of packages to ignore for each backend.
+            pub fn ignored(&self) -> PackageIds {
+                PackageIds(BTreeMap::from([
+                    $( (AnyBackend::$backend, self.$backend.ignore.iter().cloned().collect()), )*
+                ]))
+            }
+
+            pub fn check_keys(table: &dyn TableLike, checker: &mut KeyChecker) {
+                checker.check_backend_table(table, &[], "the [backends] config table");
+
+                for (key, item) in table.iter() {
+                    let Some(backend_table) = item.as_table_like() else {
+                        continue;
+                    };
+
+                    $(
+                        if key.to_lowercase() == AnyBackend::$backend.to_string().to_lowercase() {
+                            checker.check_table(
+                                backend_table,
+                                struct_fields::<<$backend as Backend>::Config>(),
+                                &format!("the [backends.{key}] config table"),
+                            );
+                        }
+                    )*
+                }
+            }
+        }
+        impl schemars::JsonSchema for BackendConfigs {
+            fn schema_name() -> String {
+                "BackendConfigs".to_string()
+            }
+
+            fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+                let mut schema = SchemaObject {
+                    instance_type: Some(InstanceType::Object.into()),
+                    ..Default::default()
+                };
+
+                let object = schema.object();
+                $(
+                    object.properties.insert(
+                        AnyBackend::$backend.to_string().to_lowercase(),
+                        gen.subschema_for::<<$backend as Backend>::Config>(),
+                    );
+                )*
+                object.additional_properties = Some(Box::new(false.into()));
+
+                schema.into()
+            }
+        }
+        impl Serialize for BackendConfigs {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                use serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(None)?;
+                $(
+                    map.serialize_entry(&AnyBackend::$backend.to_string().to_lowercase(), &self.$backend)?;
+                )*
+                map.end()
+            }
+        }
+        impl<'de> Deserialize<'de> for BackendConfigs {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let table = toml::Table::deserialize(deserializer)?;
+
+                let mut backend_configs = Self::default();
+                'keys: for (key, value) in table {
+                    $(
+                        if key.to_lowercase() == AnyBackend::$backend.to_string().to_lowercase() {
+                            backend_configs.$backend = value.try_into().map_err(serde::de::Error::custom)?;
+                            continue 'keys;
+                        }
+                    )*
+
+                    log::warn!("unrecognised backend: {key:?} in the [backends] config table");
+                }
+
+                Ok(backend_configs)
+            }
+        }
+    }
+}
+apply_public_backends!(backend_configs);
+
 fn is_enabled(backend: AnyBackend, config: &Config) -> bool {
-    !config
-        .disabled_backends
-        .iter()
-        .any(|x| x.to_lowercase() == backend.to_string().to_lowercase())
+    config.backends.is_enabled(backend)
+        && !config
+            .disabled_backends
+            .iter()
+            .any(|x| x.to_lowercase() == backend.to_string().to_lowercase())
 }
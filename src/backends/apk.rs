@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::{command_found, run_command};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Apk;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApkQueryInfo {
+    /// the version constraint recorded in the world file, such as ">=1.36"
+    pub version: Option<String>,
+    /// the repository tag the package is pinned to, such as "edge"
+    pub tag: Option<String>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct ApkInstallOptions {
+    /// a version constraint, such as "=1.36.1-r0", ">=1.36" or "~1.36"
+    version: Option<String>,
+    /// the tag of the repository to pin the package to, such as "edge"
+    tag: Option<String>,
+}
+impl MergeInstallOptions for ApkInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = ApkInstallOptions {
+            version: merge_optionals("version", self.version, other.version, &mut conflicts),
+            tag: merge_optionals("tag", self.tag, other.tag, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ApkConfig {
+    #[serde_inline_default(ApkConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(ApkConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(ApkConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for ApkConfig {
+    fn default() -> Self {
+        ApkConfig {
+            enabled: true,
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// The file in which apk records the explicitly installed packages.
+const WORLD_PATH: &str = "/etc/apk/world";
+
+impl Backend for Apk {
+    type QueryInfo = ApkQueryInfo;
+    type InstallOptions = ApkInstallOptions;
+    type Config = ApkConfig;
+
+    fn is_available(_: &Config) -> bool {
+        command_found("apk")
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let world =
+            std::fs::read_to_string(WORLD_PATH).wrap_err(eyre!("reading {WORLD_PATH:?}"))?;
+
+        Ok(extract_packages(&world))
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        no_confirm: bool,
+        config: &Config,
+    ) -> Result<()> {
+        if !packages.is_empty() {
+            // apk takes both in the same form as its world file, such as
+            // "curl@edge>=8.0"
+            let packages = packages
+                .iter()
+                .map(|(package, options)| {
+                    format!(
+                        "{package}{}{}",
+                        options
+                            .tag
+                            .as_ref()
+                            .map(|tag| format!("@{tag}"))
+                            .unwrap_or_default(),
+                        options.version.as_deref().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // apk never asks for confirmation unless told to
+            run_command(
+                ["apk", "add"]
+                    .into_iter()
+                    .chain(Some("--interactive").filter(|_| !no_confirm))
+                    .chain(config.backends.Apk.extra_args.iter().map(String::as_str))
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Sudo,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, no_confirm: bool, _: &Config) -> Result<()> {
+        if !packages.is_empty() {
+            run_command(
+                ["apk", "del"]
+                    .into_iter()
+                    .chain(Some("--interactive").filter(|_| !no_confirm))
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Sudo,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the packages from the world file, which lists one dependency per
+/// line with an optional repository tag and version constraint, skipping
+/// the conflicts which start with a `!`, such as:
+///
+/// ```text
+/// alpine-base
+/// busybox>=1.36
+/// curl@edge
+/// !nano
+/// ```
+fn extract_packages(world: &str) -> BTreeMap<String, ApkQueryInfo> {
+    world
+        .split_whitespace()
+        .filter(|dependency| !dependency.starts_with('!'))
+        .map(|dependency| {
+            let (name_and_tag, version) = match dependency.find(['=', '<', '>', '~']) {
+                Some(index) => (&dependency[..index], Some(&dependency[index..])),
+                None => (dependency, None),
+            };
+
+            let (name, tag) = match name_and_tag.split_once('@') {
+                Some((name, tag)) => (name, Some(tag)),
+                None => (name_and_tag, None),
+            };
+
+            (
+                name.to_string(),
+                ApkQueryInfo {
+                    version: version.map(String::from),
+                    tag: tag.map(String::from),
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_apk_world_packages() {
+        let world = "\
+alpine-base
+busybox>=1.36
+curl@edge
+vim@edge=9.1.0-r0
+!nano
+";
+
+        let packages = extract_packages(world);
+
+        assert_eq!(
+            packages.keys().collect::<Vec<_>>(),
+            ["alpine-base", "busybox", "curl", "vim"]
+        );
+
+        assert_eq!(packages["alpine-base"].version, None);
+        assert_eq!(packages["alpine-base"].tag, None);
+        assert_eq!(packages["busybox"].version.as_deref(), Some(">=1.36"));
+        assert_eq!(packages["busybox"].tag, None);
+        assert_eq!(packages["curl"].version, None);
+        assert_eq!(packages["curl"].tag.as_deref(), Some("edge"));
+        assert_eq!(packages["vim"].version.as_deref(), Some("=9.1.0-r0"));
+        assert_eq!(packages["vim"].tag.as_deref(), Some("edge"));
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use color_eyre::Result;
 use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
 
 use crate::cmd::{command_found, run_command, run_command_for_stdout};
 use crate::prelude::*;
@@ -12,12 +13,54 @@ pub struct Apt;
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AptQueryInfo {}
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct AptInstallOptions {}
+impl MergeInstallOptions for AptInstallOptions {
+    fn merge(self, _: Self) -> Result<Self, Vec<String>> {
+        Ok(self)
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct AptConfig {
+    #[serde_inline_default(AptConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(AptConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(AptConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for AptConfig {
+    fn default() -> Self {
+        AptConfig {
+            enabled: true,
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
 
 impl Backend for Apt {
     type QueryInfo = AptQueryInfo;
     type InstallOptions = AptInstallOptions;
+    type Config = AptConfig;
+
+    fn is_available(_: &Config) -> bool {
+        command_found("apt-mark")
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -26,8 +69,8 @@ impl Backend for Apt {
         Ok(packages)
     }
 
-    fn query_installed_packages(_: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("apt-mark") {
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
@@ -48,13 +91,14 @@ impl Backend for Apt {
     fn install_packages(
         packages: &BTreeMap<String, Self::InstallOptions>,
         no_confirm: bool,
-        _: &Config,
+        config: &Config,
     ) -> Result<()> {
         if !packages.is_empty() {
             run_command(
                 ["apt-get", "install"]
                     .into_iter()
                     .chain(Some("--yes").filter(|_| no_confirm))
+                    .chain(config.backends.Apt.extra_args.iter().map(String::as_str))
                     .chain(packages.keys().map(String::as_str)),
                 Perms::Sudo,
             )?;
@@ -13,27 +13,100 @@ pub struct Arch;
 pub struct ArchQueryInfo {}
 
 #[serde_inline_default]
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct ArchInstallOptions {
     #[serde_inline_default(ArchInstallOptions::default().optional_deps)]
     pub optional_deps: Vec<String>,
 }
+impl MergeInstallOptions for ArchInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        Ok(ArchInstallOptions {
+            optional_deps: merge_lists(self.optional_deps, other.optional_deps),
+        })
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ArchConfig {
+    #[serde_inline_default(ArchConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(ArchConfig::default().package_manager)]
+    pub package_manager: ArchPackageManager,
+    #[serde_inline_default(ArchConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(ArchConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for ArchConfig {
+    fn default() -> Self {
+        ArchConfig {
+            enabled: true,
+            package_manager: ArchPackageManager::default(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchPackageManager {
+    #[default]
+    Pacman,
+    Paru,
+    Yay,
+}
+impl ArchPackageManager {
+    pub fn as_command(&self) -> &'static str {
+        match self {
+            ArchPackageManager::Pacman => "pacman",
+            ArchPackageManager::Paru => "paru",
+            ArchPackageManager::Yay => "yay",
+        }
+    }
+
+    pub fn change_perms(&self) -> Perms {
+        match self {
+            ArchPackageManager::Pacman => Perms::Sudo,
+            ArchPackageManager::Paru => Perms::Same,
+            ArchPackageManager::Yay => Perms::Same,
+        }
+    }
+}
 
 impl Backend for Arch {
     type QueryInfo = ArchQueryInfo;
     type InstallOptions = ArchInstallOptions;
+    type Config = ArchConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(config.backends.Arch.package_manager.as_command())
+    }
 
     fn map_managed_packages(
         mut packages: BTreeMap<String, Self::InstallOptions>,
         config: &Config,
     ) -> Result<BTreeMap<String, Self::InstallOptions>> {
-        if !command_found(config.arch_package_manager.as_command()) {
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
         let groups = run_command_for_stdout(
             [
-                config.arch_package_manager.as_command(),
+                config.backends.Arch.package_manager.as_command(),
                 "--sync",
                 "--groups",
                 "--quiet",
@@ -45,7 +118,7 @@ impl Backend for Arch {
             if let Some(install_options) = packages.remove(group) {
                 let group_packages = run_command_for_stdout(
                     [
-                        config.arch_package_manager.as_command(),
+                        config.backends.Arch.package_manager.as_command(),
                         "--sync",
                         "--groups",
                         "--quiet",
@@ -69,13 +142,13 @@ impl Backend for Arch {
     }
 
     fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found(config.arch_package_manager.as_command()) {
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
         let explicit_packages = run_command_for_stdout(
             [
-                config.arch_package_manager.as_command(),
+                config.backends.Arch.package_manager.as_command(),
                 "--query",
                 "--explicit",
                 "--quiet",
@@ -100,19 +173,20 @@ impl Backend for Arch {
         if !packages.is_empty() {
             run_command(
                 [
-                    config.arch_package_manager.as_command(),
+                    config.backends.Arch.package_manager.as_command(),
                     "--sync",
                     "--asexplicit",
                 ]
                 .into_iter()
                 .chain(Some("--no_confirm").filter(|_| no_confirm))
+                .chain(config.backends.Arch.extra_args.iter().map(String::as_str))
                 .chain(packages.keys().map(String::as_str))
                 .chain(
                     packages.values().flat_map(|dependencies| {
                         dependencies.optional_deps.iter().map(String::as_str)
                     }),
                 ),
-                config.arch_package_manager.change_perms(),
+                config.backends.Arch.package_manager.change_perms(),
             )?;
         }
 
@@ -127,18 +201,18 @@ impl Backend for Arch {
         if !packages.is_empty() {
             run_command(
                 [
-                    config.arch_package_manager.as_command(),
+                    config.backends.Arch.package_manager.as_command(),
                     "--database",
                     "--asdeps",
                 ]
                 .into_iter()
                 .chain(packages.iter().map(String::as_str)),
-                config.arch_package_manager.change_perms(),
+                config.backends.Arch.package_manager.change_perms(),
             )?;
 
             let orphans_output = run_command_for_stdout(
                 [
-                    config.arch_package_manager.as_command(),
+                    config.backends.Arch.package_manager.as_command(),
                     "--query",
                     "--deps",
                     "--unrequired",
@@ -150,7 +224,7 @@ impl Backend for Arch {
 
             run_command(
                 [
-                    config.arch_package_manager.as_command(),
+                    config.backends.Arch.package_manager.as_command(),
                     "--remove",
                     "--nosave",
                     "--recursive",
@@ -158,7 +232,7 @@ impl Backend for Arch {
                 .into_iter()
                 .chain(Some("--noconfirm").filter(|_| no_confirm))
                 .chain(orphans),
-                config.arch_package_manager.change_perms(),
+                config.backends.Arch.package_manager.change_perms(),
             )?;
         }
 
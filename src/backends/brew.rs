@@ -0,0 +1,196 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::Result;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Brew;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BrewQueryInfo {
+    pub cask: bool,
+}
+
+#[serde_inline_default]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct BrewInstallOptions {
+    /// a tap to add before installing the package, such as "hashicorp/tap"
+    tap: Option<String>,
+    /// whether the package is a cask rather than a formula
+    #[serde_inline_default(BrewInstallOptions::default().cask)]
+    cask: bool,
+}
+impl MergeInstallOptions for BrewInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = BrewInstallOptions {
+            tap: merge_optionals("tap", self.tap, other.tap, &mut conflicts),
+            // a formula and a cask of the same name are different packages
+            cask: merge_optionals("cask", Some(self.cask), Some(other.cask), &mut conflicts)
+                .unwrap_or_default(),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BrewConfig {
+    #[serde_inline_default(BrewConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(BrewConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(BrewConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(BrewConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for BrewConfig {
+    fn default() -> Self {
+        BrewConfig {
+            enabled: true,
+            executable: "brew".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl Backend for Brew {
+    type QueryInfo = BrewQueryInfo;
+    type InstallOptions = BrewInstallOptions;
+    type Config = BrewConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Brew.executable)
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages
+            .into_iter()
+            .filter_map(|(package, options)| {
+                if options.cask && !cfg!(target_os = "macos") {
+                    log::warn!(
+                        "skipping the brew cask {package:?} as casks are only supported on macOS"
+                    );
+                    return None;
+                }
+
+                // brew lists packages from taps by their full name, such as
+                // "hashicorp/tap/terraform"
+                let package = match &options.tap {
+                    Some(tap) if !package.contains('/') => format!("{tap}/{package}"),
+                    _ => package,
+                };
+
+                Some((package, options))
+            })
+            .collect())
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let executable = config.backends.Brew.executable.as_str();
+
+        let formulae = run_command_for_stdout(
+            [executable, "leaves", "--installed-on-request"],
+            Perms::Same,
+        )?;
+
+        // casks are only supported on macOS
+        let casks = if cfg!(target_os = "macos") {
+            run_command_for_stdout(
+                [executable, "list", "--cask", "--full-name", "-1"],
+                Perms::Same,
+            )?
+        } else {
+            String::new()
+        };
+
+        Ok(formulae
+            .lines()
+            .map(|x| (x.trim().to_string(), BrewQueryInfo { cask: false }))
+            .chain(
+                casks
+                    .lines()
+                    .map(|x| (x.trim().to_string(), BrewQueryInfo { cask: true })),
+            )
+            .filter(|(x, _)| !x.is_empty())
+            .collect())
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        let executable = config.backends.Brew.executable.as_str();
+
+        for tap in packages.values().filter_map(|x| x.tap.as_deref()).unique() {
+            run_command([executable, "tap", tap], Perms::Same)?;
+        }
+
+        for cask in [false, true] {
+            let packages = packages
+                .iter()
+                .filter(|(_, options)| options.cask == cask)
+                .map(|(package, _)| package.as_str())
+                .collect::<Vec<_>>();
+
+            if !packages.is_empty() {
+                run_command(
+                    [executable, "install"]
+                        .into_iter()
+                        .chain(Some("--cask").filter(|_| cask))
+                        .chain(config.backends.Brew.extra_args.iter().map(String::as_str))
+                        .chain(packages),
+                    Perms::Same,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        if !packages.is_empty() {
+            run_command(
+                [config.backends.Brew.executable.as_str(), "uninstall"]
+                    .into_iter()
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+}
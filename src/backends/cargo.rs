@@ -23,7 +23,18 @@ pub struct CargoQueryInfo {
 }
 
 #[serde_inline_default]
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct CargoInstallOptions {
     git: Option<String>,
     #[serde_inline_default(CargoInstallOptions::default().all_features)]
@@ -33,10 +44,58 @@ pub struct CargoInstallOptions {
     #[serde_inline_default(CargoInstallOptions::default().features)]
     features: Vec<String>,
 }
+impl MergeInstallOptions for CargoInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = CargoInstallOptions {
+            git: merge_optionals("git", self.git, other.git, &mut conflicts),
+            // install with the union of the features wanted by each declaration
+            all_features: self.all_features || other.all_features,
+            no_default_features: self.no_default_features && other.no_default_features,
+            features: merge_lists(self.features, other.features),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct CargoConfig {
+    #[serde_inline_default(CargoConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(CargoConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(CargoConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(CargoConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for CargoConfig {
+    fn default() -> Self {
+        CargoConfig {
+            enabled: true,
+            executable: "cargo".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
 
 impl Backend for Cargo {
     type QueryInfo = CargoQueryInfo;
     type InstallOptions = CargoInstallOptions;
+    type Config = CargoConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Cargo.executable)
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -45,8 +104,8 @@ impl Backend for Cargo {
         Ok(packages)
     }
 
-    fn query_installed_packages(_: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("cargo") {
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
@@ -69,12 +128,13 @@ impl Backend for Cargo {
     fn install_packages(
         packages: &BTreeMap<String, Self::InstallOptions>,
         _: bool,
-        _: &Config,
+        config: &Config,
     ) -> Result<()> {
         for (package, options) in packages {
             run_command(
-                ["cargo", "install"]
+                [config.backends.Cargo.executable.as_str(), "install"]
                     .into_iter()
+                    .chain(config.backends.Cargo.extra_args.iter().map(String::as_str))
                     .chain(Some("--git").into_iter().filter(|_| options.git.is_some()))
                     .chain(options.git.as_deref())
                     .chain(
@@ -101,10 +161,10 @@ impl Backend for Cargo {
         Ok(())
     }
 
-    fn remove_packages(packages: &BTreeSet<String>, _: bool, _: &Config) -> Result<()> {
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
         if !packages.is_empty() {
             run_command(
-                ["cargo", "uninstall"]
+                [config.backends.Cargo.executable.as_str(), "uninstall"]
                     .into_iter()
                     .chain(packages.iter().map(String::as_str)),
                 Perms::Same,
@@ -168,3 +228,38 @@ fn extract_packages(contents: &str) -> Result<BTreeMap<String, CargoQueryInfo>>
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_cargo_install_options() {
+        let declaration = |git: Option<&str>, features: &[&str]| CargoInstallOptions {
+            git: git.map(String::from),
+            features: features.iter().map(|x| x.to_string()).collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            declaration(Some("https://example.com/a"), &["x"])
+                .merge(declaration(Some("https://example.com/a"), &["x"])),
+            Ok(declaration(Some("https://example.com/a"), &["x"]))
+        );
+
+        assert_eq!(
+            declaration(Some("https://example.com/a"), &["x", "y"])
+                .merge(declaration(None, &["z", "x"])),
+            Ok(declaration(Some("https://example.com/a"), &["x", "y", "z"]))
+        );
+
+        assert_eq!(
+            declaration(Some("https://example.com/a"), &[])
+                .merge(declaration(Some("https://example.com/b"), &[])),
+            Err(vec![
+                "git is set to both \"https://example.com/a\" and \"https://example.com/b\""
+                    .to_string()
+            ])
+        );
+    }
+}
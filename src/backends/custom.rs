@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+use serde_json::Value;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+/// The backend for all the package managers defined in the
+/// `[custom_backends]` config table, whose package ids are written as
+/// `<custom backend name>:<package>`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Custom;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CustomQueryInfo {}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct CustomInstallOptions {}
+impl MergeInstallOptions for CustomInstallOptions {
+    fn merge(self, _: Self) -> Result<Self, Vec<String>> {
+        Ok(self)
+    }
+}
+
+/// The config of all the custom backends at once, each custom backend has its
+/// own `extra_args` and `ignore` in its [`CustomBackendDefinition`].
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct CustomConfig {
+    #[serde_inline_default(CustomConfig::default().enabled)]
+    pub enabled: bool,
+    /// the ignore patterns of every custom backend definition as
+    /// `<custom backend name>:<pattern>`, filled in when loading the config
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub ignore: Vec<String>,
+}
+impl Default for CustomConfig {
+    fn default() -> Self {
+        CustomConfig {
+            enabled: true,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// A package manager defined by the commands metapac should run to query,
+/// install and remove its packages.
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct CustomBackendDefinition {
+    #[serde_inline_default(CustomBackendDefinition::default().enabled)]
+    pub enabled: bool,
+    /// the command which lists the explicitly installed packages
+    #[serde(deserialize_with = "deserialize_command")]
+    #[schemars(length(min = 1))]
+    pub query: Vec<String>,
+    /// a dot-separated path to an array of package names or to an object
+    /// with package names as keys in the JSON output of the query command,
+    /// otherwise the first word of each line of its output is used
+    #[serde_inline_default(CustomBackendDefinition::default().query_json_path)]
+    pub query_json_path: Option<String>,
+    /// the command which installs packages
+    #[serde(deserialize_with = "deserialize_command")]
+    #[schemars(length(min = 1))]
+    pub install: Vec<String>,
+    /// the command which removes packages
+    #[serde(deserialize_with = "deserialize_command")]
+    #[schemars(length(min = 1))]
+    pub remove: Vec<String>,
+    /// arguments added to the install and remove commands when not asking
+    /// for confirmation
+    #[serde_inline_default(CustomBackendDefinition::default().no_confirm_args)]
+    pub no_confirm_args: Vec<String>,
+    /// whether the install and remove commands need to be run with sudo
+    #[serde_inline_default(CustomBackendDefinition::default().perms)]
+    pub perms: Perms,
+    #[serde_inline_default(CustomBackendDefinition::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(CustomBackendDefinition::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for CustomBackendDefinition {
+    fn default() -> Self {
+        CustomBackendDefinition {
+            enabled: true,
+            query: Vec::new(),
+            query_json_path: None,
+            install: Vec::new(),
+            remove: Vec::new(),
+            no_confirm_args: Vec::new(),
+            perms: Perms::default(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// Deserializes a command, which must at least have the program to run.
+fn deserialize_command<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let command = Vec::<String>::deserialize(deserializer)?;
+
+    if command.is_empty() {
+        Err(serde::de::Error::custom(
+            "the command must not be empty, it needs at least the program to run",
+        ))
+    } else {
+        Ok(command)
+    }
+}
+
+/// The placeholder argument in install and remove commands which is replaced
+/// by the packages, otherwise they are appended to the end of the command.
+const PACKAGES_PLACEHOLDER: &str = "{packages}";
+
+impl Backend for Custom {
+    type QueryInfo = CustomQueryInfo;
+    type InstallOptions = CustomInstallOptions;
+    type Config = CustomConfig;
+
+    fn is_available(config: &Config) -> bool {
+        enabled_definitions(config).any(|(_, definition)| definition.is_available())
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        config: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        for package in packages.keys() {
+            let (name, _) = split_package_id(package)?;
+
+            if !config.custom_backends.contains_key(name) {
+                return Err(eyre!(
+                    "the custom backend {name:?} of the package {package:?} is not defined in the [custom_backends] config table"
+                ));
+            }
+        }
+
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        let mut packages = BTreeMap::new();
+
+        for (name, definition) in enabled_definitions(config) {
+            if !definition.is_available() {
+                log::warn!(
+                    "skipping the custom backend {name:?} as its query command was not found"
+                );
+                continue;
+            }
+
+            let stdout = run_command_for_stdout(definition.query.iter().cloned(), Perms::Same)?;
+
+            let names = match &definition.query_json_path {
+                Some(path) => extract_json_package_names(&stdout, path).wrap_err(eyre!(
+                    "parsing the query output of the custom backend {name:?}"
+                ))?,
+                None => stdout
+                    .lines()
+                    .filter_map(|x| x.split_whitespace().next())
+                    .map(String::from)
+                    .collect(),
+            };
+
+            packages.extend(
+                names
+                    .into_iter()
+                    .map(|package| (format!("{name}:{package}"), CustomQueryInfo {})),
+            );
+        }
+
+        Ok(packages)
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        no_confirm: bool,
+        config: &Config,
+    ) -> Result<()> {
+        for (name, packages) in group_by_backend(packages.keys())? {
+            let definition = definition(name, config)?;
+
+            if !is_enabled(name, definition, config) {
+                continue;
+            }
+
+            run_command(
+                definition.command(
+                    &definition.install,
+                    &definition.extra_args,
+                    &packages,
+                    no_confirm,
+                ),
+                definition.perms,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(
+        packages: &BTreeSet<String>,
+        no_confirm: bool,
+        config: &Config,
+    ) -> Result<()> {
+        for (name, packages) in group_by_backend(packages.iter())? {
+            let definition = definition(name, config)?;
+
+            if !is_enabled(name, definition, config) {
+                continue;
+            }
+
+            run_command(
+                definition.command(&definition.remove, &[], &packages, no_confirm),
+                definition.perms,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl CustomBackendDefinition {
+    fn is_available(&self) -> bool {
+        self.query.first().is_some_and(|x| command_found(x))
+    }
+
+    fn command<'a>(
+        &'a self,
+        command: &'a [String],
+        extra_args: &'a [String],
+        packages: &[&'a str],
+        no_confirm: bool,
+    ) -> Vec<&'a str> {
+        let mut args = command
+            .iter()
+            .chain(self.no_confirm_args.iter().filter(|_| no_confirm))
+            .chain(extra_args.iter())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        match args.iter().position(|x| *x == PACKAGES_PLACEHOLDER) {
+            Some(index) => {
+                args.splice(index..=index, packages.iter().copied());
+            }
+            None => args.extend(packages),
+        }
+
+        args
+    }
+}
+
+fn split_package_id(package: &str) -> Result<(&str, &str)> {
+    package.split_once(':').ok_or(eyre!(
+        "the custom backend package {package:?} must be written as \"<custom backend name>:<package>\""
+    ))
+}
+
+fn group_by_backend<'a>(
+    packages: impl Iterator<Item = &'a String>,
+) -> Result<BTreeMap<&'a str, Vec<&'a str>>> {
+    let mut grouped: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for package in packages {
+        let (name, package) = split_package_id(package)?;
+        grouped.entry(name).or_default().push(package);
+    }
+
+    Ok(grouped)
+}
+
+/// The ignore patterns of every custom backend as
+/// `<custom backend name>:<pattern>`.
+pub fn ignore_patterns(custom_backends: &BTreeMap<String, CustomBackendDefinition>) -> Vec<String> {
+    custom_backends
+        .iter()
+        .flat_map(|(name, definition)| {
+            definition
+                .ignore
+                .iter()
+                .map(move |pattern| format!("{name}:{pattern}"))
+        })
+        .collect()
+}
+
+/// Whether the custom backend is enabled in its definition and not listed in
+/// `disabled_backends`.
+fn is_enabled(name: &str, definition: &CustomBackendDefinition, config: &Config) -> bool {
+    definition.enabled
+        && !config
+            .disabled_backends
+            .iter()
+            .any(|x| x.to_lowercase() == name.to_lowercase())
+}
+
+fn enabled_definitions(
+    config: &Config,
+) -> impl Iterator<Item = (&String, &CustomBackendDefinition)> {
+    config
+        .custom_backends
+        .iter()
+        .filter(|(name, definition)| is_enabled(name, definition, config))
+}
+
+fn definition<'a>(name: &str, config: &'a Config) -> Result<&'a CustomBackendDefinition> {
+    config.custom_backends.get(name).ok_or(eyre!(
+        "the custom backend {name:?} is not defined in the [custom_backends] config table"
+    ))
+}
+
+fn extract_json_package_names(stdout: &str, path: &str) -> Result<BTreeSet<String>> {
+    let value: Value = serde_json::from_str(stdout)?;
+
+    let value = path
+        .split('.')
+        .filter(|x| !x.is_empty())
+        .try_fold(&value, |value, key| match value {
+            Value::Array(array) => key.parse::<usize>().ok().and_then(|x| array.get(x)),
+            _ => value.get(key),
+        })
+        .ok_or(eyre!("the JSON path {path:?} was not found"))?;
+
+    match value {
+        Value::Array(array) => array
+            .iter()
+            .map(|x| {
+                x.as_str()
+                    .map(String::from)
+                    .ok_or(eyre!("the array at the JSON path {path:?} must only contain strings"))
+            })
+            .collect(),
+        Value::Object(object) => Ok(object.keys().cloned().collect()),
+        _ => Err(eyre!(
+            "the JSON path {path:?} must be an array of package names or an object with package names as keys"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn command_packages_placeholder() {
+        let definition = CustomBackendDefinition {
+            no_confirm_args: strings(&["--yes"]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            definition.command(
+                &strings(&["tool", "add", "{packages}", "--global"]),
+                &strings(&["--quiet"]),
+                &["a", "b"],
+                true,
+            ),
+            ["tool", "add", "a", "b", "--global", "--yes", "--quiet"]
+        );
+
+        assert_eq!(
+            definition.command(&strings(&["tool", "remove"]), &[], &["a", "b"], false),
+            ["tool", "remove", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn extract_json_array_and_object_package_names() {
+        assert_eq!(
+            extract_json_package_names(r#"{"data": {"packages": ["a", "b"]}}"#, "data.packages")
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+
+        assert_eq!(
+            extract_json_package_names(r#"[{"a": {"version": "1.0"}, "b": {}}]"#, "0")
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+
+        assert!(extract_json_package_names(r#"{"packages": "a"}"#, "packages").is_err());
+        assert!(extract_json_package_names(r#"{"packages": [1]}"#, "packages").is_err());
+        assert!(extract_json_package_names(r#"{}"#, "packages").is_err());
+    }
+}
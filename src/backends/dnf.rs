@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use color_eyre::Result;
 use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
 
 use crate::cmd::{command_found, run_command, run_command_for_stdout};
 use crate::prelude::*;
@@ -14,14 +15,69 @@ pub struct DnfQueryInfo {
     pub user: bool,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct DnfInstallOptions {
     repo: Option<String>,
 }
+impl MergeInstallOptions for DnfInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = DnfInstallOptions {
+            repo: merge_optionals("repo", self.repo, other.repo, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DnfConfig {
+    #[serde_inline_default(DnfConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(DnfConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(DnfConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(DnfConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for DnfConfig {
+    fn default() -> Self {
+        DnfConfig {
+            enabled: true,
+            executable: "dnf".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
 
 impl Backend for Dnf {
     type QueryInfo = DnfQueryInfo;
     type InstallOptions = DnfInstallOptions;
+    type Config = DnfConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Dnf.executable)
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -30,14 +86,16 @@ impl Backend for Dnf {
         Ok(packages)
     }
 
-    fn query_installed_packages(_: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("dnf") {
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        let executable = config.backends.Dnf.executable.as_str();
+
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
         let system_packages = run_command_for_stdout(
             [
-                "dnf",
+                executable,
                 "repoquery",
                 "--installed",
                 "--queryformat",
@@ -49,7 +107,7 @@ impl Backend for Dnf {
 
         let user_packages = run_command_for_stdout(
             [
-                "dnf",
+                executable,
                 "repoquery",
                 "--userinstalled",
                 "--queryformat",
@@ -68,23 +126,31 @@ impl Backend for Dnf {
     fn install_packages(
         packages: &BTreeMap<String, Self::InstallOptions>,
         no_confirm: bool,
-        _: &Config,
+        config: &Config,
     ) -> Result<()> {
         if !packages.is_empty() {
             // add these two repositories as these are needed for many dependencies
             #[allow(clippy::option_if_let_else)]
             run_command(
-                ["dnf", "install", "--repo", "updates", "--repo", "fedora"]
-                    .into_iter()
-                    .chain(Some("--assumeyes").filter(|_| no_confirm))
-                    .chain(
-                        packages
-                            .iter()
-                            .flat_map(|(package_id, options)| match &options.repo {
-                                Some(repo) => vec![package_id, "--repo", repo.as_str()],
-                                None => vec![package_id.as_str()],
-                            }),
-                    ),
+                [
+                    config.backends.Dnf.executable.as_str(),
+                    "install",
+                    "--repo",
+                    "updates",
+                    "--repo",
+                    "fedora",
+                ]
+                .into_iter()
+                .chain(Some("--assumeyes").filter(|_| no_confirm))
+                .chain(config.backends.Dnf.extra_args.iter().map(String::as_str))
+                .chain(
+                    packages
+                        .iter()
+                        .flat_map(|(package_id, options)| match &options.repo {
+                            Some(repo) => vec![package_id, "--repo", repo.as_str()],
+                            None => vec![package_id.as_str()],
+                        }),
+                ),
                 Perms::Sudo,
             )?;
         }
@@ -92,10 +158,14 @@ impl Backend for Dnf {
         Ok(())
     }
 
-    fn remove_packages(packages: &BTreeSet<String>, no_confirm: bool, _: &Config) -> Result<()> {
+    fn remove_packages(
+        packages: &BTreeSet<String>,
+        no_confirm: bool,
+        config: &Config,
+    ) -> Result<()> {
         if !packages.is_empty() {
             run_command(
-                ["dnf", "remove"]
+                [config.backends.Dnf.executable.as_str(), "remove"]
                     .into_iter()
                     .chain(Some("--assumeyes").filter(|_| no_confirm))
                     .chain(packages.iter().map(String::as_str)),
@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use color_eyre::Result;
 use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
 
 use crate::cmd::{command_found, run_command, run_command_for_stdout};
 use crate::prelude::*;
@@ -14,12 +15,60 @@ pub struct FlatpakQueryInfo {
     pub systemwide: bool,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct FlatpakInstallOptions {}
+impl MergeInstallOptions for FlatpakInstallOptions {
+    fn merge(self, _: Self) -> Result<Self, Vec<String>> {
+        Ok(self)
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct FlatpakConfig {
+    #[serde_inline_default(FlatpakConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(FlatpakConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(FlatpakConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(FlatpakConfig::default().ignore)]
+    pub ignore: Vec<String>,
+    #[serde_inline_default(FlatpakConfig::default().systemwide)]
+    pub systemwide: bool,
+}
+impl Default for FlatpakConfig {
+    fn default() -> Self {
+        FlatpakConfig {
+            enabled: true,
+            executable: "flatpak".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+            systemwide: true,
+        }
+    }
+}
 
 impl Backend for Flatpak {
     type QueryInfo = FlatpakQueryInfo;
     type InstallOptions = FlatpakInstallOptions;
+    type Config = FlatpakConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Flatpak.executable)
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -28,14 +77,16 @@ impl Backend for Flatpak {
         Ok(packages)
     }
 
-    fn query_installed_packages(_: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("flatpak") {
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        let executable = config.backends.Flatpak.executable.as_str();
+
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
         let sys_explicit_btree = run_command_for_stdout(
             [
-                "flatpak",
+                executable,
                 "list",
                 "--system",
                 "--app",
@@ -49,7 +100,7 @@ impl Backend for Flatpak {
 
         let user_explicit_btree = run_command_for_stdout(
             [
-                "flatpak",
+                executable,
                 "list",
                 "--user",
                 "--app",
@@ -81,9 +132,9 @@ impl Backend for Flatpak {
         if !packages.is_empty() {
             run_command(
                 [
-                    "flatpak",
+                    config.backends.Flatpak.executable.as_str(),
                     "install",
-                    if config.flatpak_systemwide {
+                    if config.backends.Flatpak.systemwide {
                         "--system"
                     } else {
                         "--user"
@@ -91,6 +142,14 @@ impl Backend for Flatpak {
                 ]
                 .into_iter()
                 .chain(Some("--assumeyes").filter(|_| no_confirm))
+                .chain(
+                    config
+                        .backends
+                        .Flatpak
+                        .extra_args
+                        .iter()
+                        .map(String::as_str),
+                )
                 .chain(packages.keys().map(String::as_str)),
                 Perms::Sudo,
             )?;
@@ -107,9 +166,9 @@ impl Backend for Flatpak {
         if !packages.is_empty() {
             run_command(
                 [
-                    "flatpak",
+                    config.backends.Flatpak.executable.as_str(),
                     "uninstall",
-                    if config.flatpak_systemwide {
+                    if config.backends.Flatpak.systemwide {
                         "--system"
                     } else {
                         "--user"
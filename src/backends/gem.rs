@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Gem;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GemQueryInfo {
+    /// the explicitly installed versions, excluding any default version
+    pub versions: Vec<String>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct GemInstallOptions {
+    /// the version requirement to install, such as "~> 2.0"
+    version: Option<String>,
+}
+impl MergeInstallOptions for GemInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = GemInstallOptions {
+            version: merge_optionals("version", self.version, other.version, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GemConfig {
+    #[serde_inline_default(GemConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(GemConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(GemConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(GemConfig::default().ignore)]
+    pub ignore: Vec<String>,
+    #[serde_inline_default(GemConfig::default().systemwide)]
+    pub systemwide: bool,
+}
+impl Default for GemConfig {
+    fn default() -> Self {
+        GemConfig {
+            enabled: true,
+            executable: "gem".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+            systemwide: false,
+        }
+    }
+}
+
+impl GemConfig {
+    fn scope_args(&self) -> Option<&'static str> {
+        Some("--user-install").filter(|_| !self.systemwide)
+    }
+
+    /// The directory gems are installed into for the configured scope.
+    fn gem_dir(&self) -> Result<String> {
+        let stdout =
+            run_command_for_stdout([self.executable.as_str(), "environment"], Perms::Same)?;
+
+        let label = if self.systemwide {
+            "- INSTALLATION DIRECTORY: "
+        } else {
+            "- USER INSTALLATION DIRECTORY: "
+        };
+
+        stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(label))
+            .map(String::from)
+            .ok_or(eyre!(
+                "getting the {:?} from gem environment",
+                label.trim_start_matches("- ").trim_end_matches(": ")
+            ))
+    }
+
+    fn change_perms(&self) -> Perms {
+        if self.systemwide {
+            Perms::Sudo
+        } else {
+            Perms::Same
+        }
+    }
+}
+
+impl Backend for Gem {
+    type QueryInfo = GemQueryInfo;
+    type InstallOptions = GemInstallOptions;
+    type Config = GemConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Gem.executable)
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let gem_config = &config.backends.Gem;
+
+        // only list the gems of the configured scope rather than every gem
+        // on the GEM_PATH, which also includes distro gems
+        let gem_dir = gem_config.gem_dir()?;
+        let stdout = run_command_for_stdout(
+            [
+                "env",
+                &format!("GEM_HOME={gem_dir}"),
+                &format!("GEM_PATH={gem_dir}"),
+                gem_config.executable.as_str(),
+                "list",
+                "--local",
+            ],
+            Perms::Same,
+        )?;
+
+        Ok(extract_packages(&stdout))
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        let gem_config = &config.backends.Gem;
+
+        // versions can only be given when installing a single gem
+        let (unversioned, versioned): (Vec<_>, Vec<_>) = packages
+            .iter()
+            .partition(|(_, options)| options.version.is_none());
+
+        if !unversioned.is_empty() {
+            run_command(
+                [gem_config.executable.as_str(), "install"]
+                    .into_iter()
+                    .chain(gem_config.scope_args())
+                    .chain(gem_config.extra_args.iter().map(String::as_str))
+                    .chain(unversioned.iter().map(|(package, _)| package.as_str())),
+                gem_config.change_perms(),
+            )?;
+        }
+
+        for (package, options) in versioned {
+            run_command(
+                [gem_config.executable.as_str(), "install"]
+                    .into_iter()
+                    .chain(gem_config.scope_args())
+                    .chain(gem_config.extra_args.iter().map(String::as_str))
+                    .chain(["--version"])
+                    .chain(options.version.as_deref())
+                    .chain([package.as_str()]),
+                gem_config.change_perms(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        let gem_config = &config.backends.Gem;
+
+        if !packages.is_empty() {
+            run_command(
+                // remove every version and the executables without asking
+                [
+                    gem_config.executable.as_str(),
+                    "uninstall",
+                    "--all",
+                    "--executables",
+                ]
+                .into_iter()
+                .chain(gem_config.scope_args())
+                .chain(packages.iter().map(String::as_str)),
+                gem_config.change_perms(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the explicitly installed gems from `gem list --local`, skipping
+/// the default gems which come with ruby itself, such as:
+///
+/// ```text
+/// bigdecimal (default: 3.1.1)
+/// json (2.7.2, default: 2.7.1)
+/// rake (13.1.0)
+/// ```
+fn extract_packages(stdout: &str) -> BTreeMap<String, GemQueryInfo> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, versions) = line.split_once(" (")?;
+
+            let versions = versions
+                .trim_end_matches(')')
+                .split(", ")
+                .filter(|version| !version.starts_with("default: "))
+                .map(String::from)
+                .collect::<Vec<_>>();
+
+            if versions.is_empty() {
+                None
+            } else {
+                Some((name.to_string(), GemQueryInfo { versions }))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,277 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Go;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GoQueryInfo {
+    /// the installed binary
+    pub binary: PathBuf,
+    /// the version of the module the binary was built from
+    pub version: String,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct GoInstallOptions {
+    /// the module version to install, such as "v0.15.0", defaults to "latest"
+    version: Option<String>,
+}
+impl MergeInstallOptions for GoInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = GoInstallOptions {
+            version: merge_optionals("version", self.version, other.version, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GoConfig {
+    #[serde_inline_default(GoConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(GoConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(GoConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(GoConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for GoConfig {
+    fn default() -> Self {
+        GoConfig {
+            enabled: true,
+            executable: "go".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl Backend for Go {
+    type QueryInfo = GoQueryInfo;
+    type InstallOptions = GoInstallOptions;
+    type Config = GoConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Go.executable)
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let bin_dir = bin_dir(config)?;
+
+        if !bin_dir.is_dir() {
+            return Ok(BTreeMap::new());
+        }
+
+        // go version -m reads the build info of every go binary in the
+        // directory
+        let stdout = run_command_for_stdout(
+            [
+                config.backends.Go.executable.as_str(),
+                "version",
+                "-m",
+                &bin_dir.to_string_lossy(),
+            ],
+            Perms::Same,
+        )?;
+
+        Ok(extract_packages(&stdout))
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        // go only installs several versioned packages at once if they are all
+        // from the same module at the same version
+        for (package, options) in packages {
+            let package = format!(
+                "{package}@{}",
+                options.version.as_deref().unwrap_or("latest")
+            );
+
+            run_command(
+                [config.backends.Go.executable.as_str(), "install"]
+                    .into_iter()
+                    .chain(config.backends.Go.extra_args.iter().map(String::as_str))
+                    .chain([package.as_str()]),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        if packages.is_empty() {
+            return Ok(());
+        }
+
+        // go has no uninstall command so delete the binaries instead
+        let installed = Self::query_installed_packages(config)?;
+
+        for package in packages {
+            let query_info = installed
+                .get(package)
+                .ok_or(eyre!("the go package {package:?} is not installed"))?;
+
+            log::info!("removing {:?}", query_info.binary);
+
+            std::fs::remove_file(&query_info.binary)
+                .wrap_err(eyre!("removing the binary of the go package {package:?}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The directory `go install` installs binaries into, which is `GOBIN` or
+/// otherwise the `bin` directory of the first `GOPATH` entry.
+fn bin_dir(config: &Config) -> Result<PathBuf> {
+    let stdout = run_command_for_stdout(
+        [
+            config.backends.Go.executable.as_str(),
+            "env",
+            "GOBIN",
+            "GOPATH",
+        ],
+        Perms::Same,
+    )?;
+
+    let mut lines = stdout.lines();
+    let gobin = lines.next().unwrap_or_default().trim();
+    let gopath = lines.next().unwrap_or_default().trim();
+
+    if !gobin.is_empty() {
+        Ok(PathBuf::from(gobin))
+    } else {
+        std::env::split_paths(gopath)
+            .next()
+            .filter(|x| !x.as_os_str().is_empty())
+            .map(|x| x.join("bin"))
+            .ok_or(eyre!("getting GOBIN or GOPATH from go env"))
+    }
+}
+
+/// Extracts the main package of each binary from `go version -m`, such as:
+///
+/// ```text
+/// /home/user/go/bin/gopls: go1.22.0
+///         path    golang.org/x/tools/gopls
+///         mod     golang.org/x/tools/gopls        v0.15.0 h1:...
+///         dep     ...
+/// ```
+fn extract_packages(stdout: &str) -> BTreeMap<String, GoQueryInfo> {
+    let mut packages = BTreeMap::new();
+
+    let mut binary = None;
+    let mut path = None;
+
+    for line in stdout.lines() {
+        if !line.starts_with('\t') {
+            binary = line.rsplit_once(": ").map(|(x, _)| PathBuf::from(x));
+            path = None;
+            continue;
+        }
+
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+
+        match fields.as_slice() {
+            ["path", package, ..] => path = Some(package.to_string()),
+            ["mod", _, version, ..] => {
+                if let (Some(binary), Some(package)) = (binary.take(), path.take()) {
+                    packages.insert(
+                        package,
+                        GoQueryInfo {
+                            binary,
+                            version: version.to_string(),
+                        },
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_go_version_packages() {
+        let stdout = "\
+/home/user/go/bin/gopls: go1.22.0
+\tpath\tgolang.org/x/tools/gopls
+\tmod\tgolang.org/x/tools/gopls\tv0.15.0\th1:abc=
+\tdep\tgolang.org/x/mod\tv0.15.0\th1:def=
+/home/user/go/bin/golangci-lint: go1.22.0
+\tpath\tgithub.com/golangci/golangci-lint/cmd/golangci-lint
+\tmod\tgithub.com/golangci/golangci-lint\tv1.56.2\th1:ghi=
+";
+
+        let packages = extract_packages(stdout);
+
+        assert_eq!(
+            packages.keys().collect::<Vec<_>>(),
+            [
+                "github.com/golangci/golangci-lint/cmd/golangci-lint",
+                "golang.org/x/tools/gopls",
+            ]
+        );
+
+        let gopls = &packages["golang.org/x/tools/gopls"];
+        assert_eq!(gopls.binary, PathBuf::from("/home/user/go/bin/gopls"));
+        assert_eq!(gopls.version, "v0.15.0");
+
+        let golangci_lint = &packages["github.com/golangci/golangci-lint/cmd/golangci-lint"];
+        assert_eq!(
+            golangci_lint.binary,
+            PathBuf::from("/home/user/go/bin/golangci-lint")
+        );
+        assert_eq!(golangci_lint.version, "v1.56.2");
+    }
+}
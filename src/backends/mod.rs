@@ -1,12 +1,22 @@
 pub mod all;
+pub mod apk;
 pub mod apt;
 pub mod arch;
+pub mod brew;
 pub mod cargo;
+pub mod custom;
 pub mod dnf;
 pub mod flatpak;
+pub mod gem;
+pub mod go;
+pub mod nix;
+pub mod npm;
 pub mod pipx;
 pub mod rustup;
+pub mod snap;
+pub mod uv;
 pub mod xbps;
+pub mod zypper;
 
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -16,19 +26,60 @@ use serde::{Deserialize, Serialize};
 
 macro_rules! apply_public_backends {
     ($macro:ident) => {
-        $macro! { Arch, Apt, Cargo, Dnf, Flatpak, Pipx, Rustup, Xbps }
+        $macro! { Arch, Apk, Apt, Brew, Cargo, Dnf, Flatpak, Gem, Go, Nix, Npm, Pipx, Rustup, Snap, Uv, Xbps, Zypper, Custom }
     };
 }
 pub(crate) use apply_public_backends;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct StringPackageStruct {
     pub package: String,
+    /// a shell command run after this package is installed
+    pub post_install: Option<String>,
+}
+
+/// How the install options of a package declared in multiple group files
+/// are combined into one.
+pub trait MergeInstallOptions: Sized {
+    /// Merges the install options of two declarations of the same package,
+    /// or returns a description of each field whose values conflict.
+    fn merge(self, other: Self) -> Result<Self, Vec<String>>;
+}
+
+/// Combines two lists of values keeping the order of their first
+/// appearances.
+pub fn merge_lists(mut list: Vec<String>, other: Vec<String>) -> Vec<String> {
+    for value in other {
+        if !list.contains(&value) {
+            list.push(value);
+        }
+    }
+    list
+}
+
+/// Combines two optional values, recording a conflict if both are set to
+/// different values.
+pub fn merge_optionals<T: PartialEq + std::fmt::Debug>(
+    field: &str,
+    value: Option<T>,
+    other: Option<T>,
+    conflicts: &mut Vec<String>,
+) -> Option<T> {
+    match (value, other) {
+        (Some(value), Some(other)) if value != other => {
+            conflicts.push(format!("{field} is set to both {value:?} and {other:?}"));
+            Some(value)
+        }
+        (value, other) => value.or(other),
+    }
 }
 
 pub trait Backend {
     type QueryInfo;
-    type InstallOptions;
+    type InstallOptions: MergeInstallOptions;
+    type Config;
+
+    fn is_available(config: &Config) -> bool;
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -49,3 +100,41 @@ pub trait Backend {
         config: &Config,
     ) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_lists_keeps_first_appearances() {
+        assert_eq!(
+            merge_lists(
+                vec!["a".to_string(), "b".to_string()],
+                vec!["c".to_string(), "a".to_string()]
+            ),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn merge_optionals_reports_conflicts() {
+        let mut conflicts = Vec::new();
+
+        assert_eq!(
+            merge_optionals("version", Some("1.0"), None, &mut conflicts),
+            Some("1.0")
+        );
+        assert_eq!(
+            merge_optionals("version", None, Some("1.0"), &mut conflicts),
+            Some("1.0")
+        );
+        assert_eq!(
+            merge_optionals("version", Some("1.0"), Some("1.0"), &mut conflicts),
+            Some("1.0")
+        );
+        assert!(conflicts.is_empty());
+
+        merge_optionals("version", Some("1.0"), Some("2.0"), &mut conflicts);
+        assert_eq!(conflicts, ["version is set to both \"1.0\" and \"2.0\""]);
+    }
+}
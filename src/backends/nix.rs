@@ -0,0 +1,290 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+use serde_json::Value;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Nix;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NixQueryInfo {
+    /// the flake the package was installed from, if known
+    pub flake: Option<String>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct NixInstallOptions {
+    /// the flake to install the package from, defaults to the flake config
+    flake: Option<String>,
+    /// the flake output to install, defaults to the package name
+    output: Option<String>,
+}
+impl MergeInstallOptions for NixInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = NixInstallOptions {
+            flake: merge_optionals("flake", self.flake, other.flake, &mut conflicts),
+            output: merge_optionals("output", self.output, other.output, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NixConfig {
+    #[serde_inline_default(NixConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(NixConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(NixConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(NixConfig::default().ignore)]
+    pub ignore: Vec<String>,
+    #[serde_inline_default(NixConfig::default().flake)]
+    pub flake: String,
+}
+impl Default for NixConfig {
+    fn default() -> Self {
+        NixConfig {
+            enabled: true,
+            executable: "nix".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+            flake: "nixpkgs".to_string(),
+        }
+    }
+}
+
+/// `nix profile` needs these experimental features, so enable them in case
+/// they aren't enabled in the user's nix config.
+const EXPERIMENTAL_FEATURES: [&str; 2] = ["--extra-experimental-features", "nix-command flakes"];
+
+impl Backend for Nix {
+    type QueryInfo = NixQueryInfo;
+    type InstallOptions = NixInstallOptions;
+    type Config = NixConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Nix.executable)
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        let mut mapped = BTreeMap::new();
+
+        for (package, options) in packages {
+            // nix names profile elements after the last attribute of the
+            // installed output, except for default outputs which are named
+            // after their flake so keep the package name for those
+            let name = match options.output.as_deref().and_then(output_name) {
+                Some(name) => name.to_string(),
+                None => package,
+            };
+
+            if mapped.insert(name.clone(), options).is_some() {
+                log::warn!("nix package {name:?} is declared by more than one flake output");
+            }
+        }
+
+        Ok(mapped)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let stdout = run_command_for_stdout(
+            [config.backends.Nix.executable.as_str()]
+                .into_iter()
+                .chain(EXPERIMENTAL_FEATURES)
+                .chain(["profile", "list", "--json"]),
+            Perms::Same,
+        )?;
+
+        extract_packages(&stdout).wrap_err("parsing the nix profile list")
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        if !packages.is_empty() {
+            let installables = packages
+                .iter()
+                .map(|(package, options)| {
+                    format!(
+                        "{}#{}",
+                        options.flake.as_ref().unwrap_or(&config.backends.Nix.flake),
+                        options.output.as_ref().unwrap_or(package),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            run_command(
+                [config.backends.Nix.executable.as_str()]
+                    .into_iter()
+                    .chain(EXPERIMENTAL_FEATURES)
+                    .chain(["profile", "install"])
+                    .chain(config.backends.Nix.extra_args.iter().map(String::as_str))
+                    .chain(installables.iter().map(String::as_str)),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        if !packages.is_empty() {
+            run_command(
+                [config.backends.Nix.executable.as_str()]
+                    .into_iter()
+                    .chain(EXPERIMENTAL_FEATURES)
+                    .chain(["profile", "remove"])
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The name nix gives the profile element of a flake output, which is the
+/// last attribute of the output unless that is "default".
+fn output_name(output: &str) -> Option<&str> {
+    output
+        .rsplit('.')
+        .next()
+        .filter(|attribute| *attribute != "default")
+}
+
+/// The name nix gives the profile element of the default output of a flake,
+/// which is the last part of its url, such as "metapac" for
+/// "github:ripytide/metapac".
+fn flake_name(url: &str) -> Option<&str> {
+    url.split(['?', '#'])
+        .next()?
+        .rsplit(['/', ':'])
+        .next()
+        .filter(|name| !name.is_empty())
+}
+
+fn extract_packages(stdout: &str) -> Result<BTreeMap<String, NixQueryInfo>> {
+    let value: Value = serde_json::from_str(stdout)?;
+
+    let query_info = |element: &Value| NixQueryInfo {
+        flake: element["originalUrl"].as_str().map(String::from),
+    };
+
+    match &value["elements"] {
+        // newer versions of nix key the profile elements by name
+        Value::Object(elements) => Ok(elements
+            .iter()
+            .map(|(name, element)| (name.clone(), query_info(element)))
+            .collect()),
+        // older versions of nix list them without names, so name them from
+        // their attribute path and flake url the same way newer versions do
+        Value::Array(elements) => Ok(elements
+            .iter()
+            .filter_map(|element| {
+                let name = match output_name(element["attrPath"].as_str()?) {
+                    Some(name) => name,
+                    None => flake_name(element["originalUrl"].as_str()?)?,
+                };
+
+                Some((name.to_string(), query_info(element)))
+            })
+            .collect()),
+        _ => Err(eyre!("getting the elements of the nix profile")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_packages_to_output_names() {
+        let packages = BTreeMap::from([
+            (
+                "rg".to_string(),
+                NixInstallOptions {
+                    flake: None,
+                    output: Some("legacyPackages.x86_64-linux.ripgrep".to_string()),
+                },
+            ),
+            (
+                "metapac".to_string(),
+                NixInstallOptions {
+                    flake: Some("github:ripytide/metapac".to_string()),
+                    output: Some("default".to_string()),
+                },
+            ),
+            ("hello".to_string(), NixInstallOptions::default()),
+        ]);
+
+        assert_eq!(
+            Nix::map_managed_packages(packages, &Config::default())
+                .unwrap()
+                .into_keys()
+                .collect::<Vec<_>>(),
+            ["hello", "metapac", "ripgrep"]
+        );
+    }
+
+    #[test]
+    fn extract_nix_profile_packages() {
+        let newer = r#"{"version": 3, "elements": {
+            "ripgrep": {"attrPath": "legacyPackages.x86_64-linux.ripgrep", "originalUrl": "flake:nixpkgs"}
+        }}"#;
+        let older = r#"{"version": 2, "elements": [
+            {"attrPath": "legacyPackages.x86_64-linux.ripgrep", "originalUrl": "flake:nixpkgs"},
+            {"attrPath": "packages.x86_64-linux.default", "originalUrl": "github:ripytide/metapac"}
+        ]}"#;
+
+        assert_eq!(
+            extract_packages(newer)
+                .unwrap()
+                .into_keys()
+                .collect::<Vec<_>>(),
+            ["ripgrep"]
+        );
+        assert_eq!(
+            extract_packages(older)
+                .unwrap()
+                .into_keys()
+                .collect::<Vec<_>>(),
+            ["metapac", "ripgrep"]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+use serde_json::Value;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Npm;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NpmQueryInfo {
+    pub version: Option<String>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct NpmInstallOptions {
+    /// the version, version range or tag to install, such as "^5.0.0"
+    version: Option<String>,
+}
+impl MergeInstallOptions for NpmInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = NpmInstallOptions {
+            version: merge_optionals("version", self.version, other.version, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NpmConfig {
+    #[serde_inline_default(NpmConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(NpmConfig::default().package_manager)]
+    pub package_manager: NpmPackageManager,
+    #[serde_inline_default(NpmConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(NpmConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for NpmConfig {
+    fn default() -> Self {
+        NpmConfig {
+            enabled: true,
+            package_manager: NpmPackageManager::default(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// The global packages which come installed with node itself rather than
+/// installed by the user.
+const NODE_PACKAGES: [&str; 2] = ["npm", "corepack"];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NpmPackageManager {
+    #[default]
+    Npm,
+    Pnpm,
+    Bun,
+}
+impl NpmPackageManager {
+    pub fn as_command(&self) -> &'static str {
+        match self {
+            NpmPackageManager::Npm => "npm",
+            NpmPackageManager::Pnpm => "pnpm",
+            NpmPackageManager::Bun => "bun",
+        }
+    }
+
+    fn install_args(&self) -> [&'static str; 2] {
+        match self {
+            NpmPackageManager::Npm => ["install", "--global"],
+            NpmPackageManager::Pnpm | NpmPackageManager::Bun => ["add", "--global"],
+        }
+    }
+
+    fn remove_args(&self) -> [&'static str; 2] {
+        match self {
+            NpmPackageManager::Npm => ["uninstall", "--global"],
+            NpmPackageManager::Pnpm | NpmPackageManager::Bun => ["remove", "--global"],
+        }
+    }
+}
+
+impl Backend for Npm {
+    type QueryInfo = NpmQueryInfo;
+    type InstallOptions = NpmInstallOptions;
+    type Config = NpmConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(config.backends.Npm.package_manager.as_command())
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let package_manager = config.backends.Npm.package_manager;
+
+        let packages = match package_manager {
+            NpmPackageManager::Npm | NpmPackageManager::Pnpm => {
+                let stdout = run_command_for_stdout(
+                    [
+                        package_manager.as_command(),
+                        "ls",
+                        "--global",
+                        "--depth=0",
+                        "--json",
+                    ],
+                    Perms::Same,
+                )?;
+
+                extract_json_packages(&stdout).wrap_err(eyre!(
+                    "parsing the global packages listed by {}",
+                    package_manager.as_command()
+                ))?
+            }
+            NpmPackageManager::Bun => {
+                let stdout = run_command_for_stdout(["bun", "pm", "ls", "--global"], Perms::Same)?;
+
+                extract_bun_packages(&stdout)
+            }
+        };
+
+        Ok(packages
+            .into_iter()
+            .filter(|(package, _)| !NODE_PACKAGES.contains(&package.as_str()))
+            .collect())
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        if !packages.is_empty() {
+            let package_manager = config.backends.Npm.package_manager;
+
+            let packages = packages
+                .iter()
+                .map(|(package, options)| match &options.version {
+                    Some(version) => format!("{package}@{version}"),
+                    None => package.clone(),
+                })
+                .collect::<Vec<_>>();
+
+            run_command(
+                [package_manager.as_command()]
+                    .into_iter()
+                    .chain(package_manager.install_args())
+                    .chain(config.backends.Npm.extra_args.iter().map(String::as_str))
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        if !packages.is_empty() {
+            let package_manager = config.backends.Npm.package_manager;
+
+            run_command(
+                [package_manager.as_command()]
+                    .into_iter()
+                    .chain(package_manager.remove_args())
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the packages from `npm ls --json`, which outputs an object, or
+/// `pnpm ls --json`, which outputs an array of objects, each with the
+/// packages in their `dependencies` field.
+fn extract_json_packages(stdout: &str) -> Result<BTreeMap<String, NpmQueryInfo>> {
+    let value: Value = serde_json::from_str(stdout)?;
+
+    let listings = match value {
+        Value::Array(listings) => listings,
+        value => vec![value],
+    };
+
+    Ok(listings
+        .iter()
+        .filter_map(|listing| listing["dependencies"].as_object())
+        .flatten()
+        .map(|(name, dependency)| {
+            (
+                name.clone(),
+                NpmQueryInfo {
+                    version: dependency["version"].as_str().map(String::from),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Extracts the packages from the tree drawn by `bun pm ls`, such as:
+///
+/// ```text
+/// /home/user/.bun/install/global node_modules (2)
+/// ├── @biomejs/biome@1.9.4
+/// └── typescript@5.6.3
+/// ```
+fn extract_bun_packages(stdout: &str) -> BTreeMap<String, NpmQueryInfo> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let package = line
+                .strip_prefix("├── ")
+                .or_else(|| line.strip_prefix("└── "))?
+                .trim();
+
+            // scoped packages also start with an @
+            Some(match package.rsplit_once('@') {
+                Some((name, version)) if !name.is_empty() => (
+                    name.to_string(),
+                    NpmQueryInfo {
+                        version: Some(version.to_string()),
+                    },
+                ),
+                _ => (package.to_string(), NpmQueryInfo { version: None }),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_npm_json_packages() {
+        let stdout = r#"{
+  "name": "lib",
+  "dependencies": {
+    "@biomejs/biome": { "version": "1.9.4" },
+    "typescript": { "version": "5.6.3" }
+  }
+}"#;
+
+        let packages = extract_json_packages(stdout).unwrap();
+
+        assert_eq!(
+            packages.keys().collect::<Vec<_>>(),
+            ["@biomejs/biome", "typescript"]
+        );
+        assert_eq!(packages["typescript"].version.as_deref(), Some("5.6.3"));
+    }
+
+    #[test]
+    fn extract_pnpm_json_packages() {
+        let stdout = r#"[
+  {
+    "path": "/home/user/.local/share/pnpm/global/5",
+    "dependencies": {
+      "typescript": { "version": "5.6.3" }
+    }
+  }
+]"#;
+
+        let packages = extract_json_packages(stdout).unwrap();
+
+        assert_eq!(packages.keys().collect::<Vec<_>>(), ["typescript"]);
+        assert_eq!(packages["typescript"].version.as_deref(), Some("5.6.3"));
+    }
+
+    #[test]
+    fn extract_bun_tree_packages() {
+        let stdout = "\
+/home/user/.bun/install/global node_modules (2)
+├── @biomejs/biome@1.9.4
+└── typescript@5.6.3
+";
+
+        let packages = extract_bun_packages(stdout);
+
+        assert_eq!(
+            packages.keys().collect::<Vec<_>>(),
+            ["@biomejs/biome", "typescript"]
+        );
+        assert_eq!(packages["@biomejs/biome"].version.as_deref(), Some("1.9.4"));
+    }
+}
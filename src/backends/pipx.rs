@@ -5,6 +5,7 @@ use color_eyre::eyre::eyre;
 use color_eyre::Result;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_inline_default::serde_inline_default;
 use serde_json::Value;
 
 use crate::cmd::command_found;
@@ -18,12 +19,57 @@ pub struct Pipx;
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct PipxQueryOptions {}
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct PipxInstallOptions {}
+impl MergeInstallOptions for PipxInstallOptions {
+    fn merge(self, _: Self) -> Result<Self, Vec<String>> {
+        Ok(self)
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct PipxConfig {
+    #[serde_inline_default(PipxConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(PipxConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(PipxConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(PipxConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for PipxConfig {
+    fn default() -> Self {
+        PipxConfig {
+            enabled: true,
+            executable: "pipx".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
 
 impl Backend for Pipx {
     type QueryInfo = PipxQueryOptions;
     type InstallOptions = PipxInstallOptions;
+    type Config = PipxConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Pipx.executable)
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -32,13 +78,15 @@ impl Backend for Pipx {
         Ok(packages)
     }
 
-    fn query_installed_packages(_: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("pipx") {
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        let executable = config.backends.Pipx.executable.as_str();
+
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
         let names = extract_package_names(run_command_for_stdout(
-            ["pipx", "list", "--json"],
+            [executable, "list", "--json"],
             Perms::Same,
         )?)?;
 
@@ -51,12 +99,13 @@ impl Backend for Pipx {
     fn install_packages(
         packages: &BTreeMap<String, Self::InstallOptions>,
         _: bool,
-        _: &Config,
+        config: &Config,
     ) -> Result<()> {
         if !packages.is_empty() {
             run_command(
-                ["pipx", "install"]
+                [config.backends.Pipx.executable.as_str(), "install"]
                     .into_iter()
+                    .chain(config.backends.Pipx.extra_args.iter().map(String::as_str))
                     .chain(packages.keys().map(String::as_str)),
                 Perms::Same,
             )?;
@@ -65,10 +114,10 @@ impl Backend for Pipx {
         Ok(())
     }
 
-    fn remove_packages(packages: &BTreeSet<String>, _: bool, _: &Config) -> Result<()> {
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
         if !packages.is_empty() {
             run_command(
-                ["pipx", "uninstall"]
+                [config.backends.Pipx.executable.as_str(), "uninstall"]
                     .into_iter()
                     .chain(packages.iter().map(String::as_str)),
                 Perms::Same,
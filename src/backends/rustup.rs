@@ -18,15 +18,51 @@ pub struct RustupQueryInfo {
 }
 
 #[serde_inline_default]
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct RustupInstallOptions {
     #[serde_inline_default(RustupInstallOptions::default().components)]
     pub components: Vec<String>,
 }
+impl MergeInstallOptions for RustupInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        Ok(RustupInstallOptions {
+            components: merge_lists(self.components, other.components),
+        })
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct RustupConfig {
+    #[serde_inline_default(RustupConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(RustupConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(RustupConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(RustupConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for RustupConfig {
+    fn default() -> Self {
+        RustupConfig {
+            enabled: true,
+            executable: "rustup".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
 
 impl Backend for Rustup {
     type QueryInfo = RustupQueryInfo;
     type InstallOptions = RustupInstallOptions;
+    type Config = RustupConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Rustup.executable)
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -35,15 +71,17 @@ impl Backend for Rustup {
         Ok(packages)
     }
 
-    fn query_installed_packages(_: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("rustup") {
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        let executable = config.backends.Rustup.executable.as_str();
+
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
         let mut packages = BTreeMap::new();
 
         let toolchains_stdout =
-            run_command_for_stdout(["rustup", "toolchain", "list"], Perms::Same)?;
+            run_command_for_stdout([executable, "toolchain", "list"], Perms::Same)?;
         let toolchains = toolchains_stdout.lines().map(|x| {
             x.split(' ')
                 .next()
@@ -57,7 +95,7 @@ impl Backend for Rustup {
             //components for custom toolchains
             if let Ok(components_stdout) = run_command_for_stdout(
                 [
-                    "rustup",
+                    executable,
                     "component",
                     "list",
                     "--installed",
@@ -81,18 +119,23 @@ impl Backend for Rustup {
     fn install_packages(
         packages: &BTreeMap<String, Self::InstallOptions>,
         _: bool,
-        _: &Config,
+        config: &Config,
     ) -> Result<()> {
+        let executable = config.backends.Rustup.executable.as_str();
+
         for (toolchain, rustup_install_options) in packages.iter() {
             run_command(
-                ["rustup", "toolchain", "install", toolchain.as_str()],
+                [executable, "toolchain", "install"]
+                    .into_iter()
+                    .chain(config.backends.Rustup.extra_args.iter().map(String::as_str))
+                    .chain([toolchain.as_str()]),
                 Perms::Same,
             )?;
 
             if !rustup_install_options.components.is_empty() {
                 run_command(
                     [
-                        "rustup",
+                        executable,
                         "component",
                         "add",
                         "--toolchain",
@@ -108,11 +151,15 @@ impl Backend for Rustup {
         Ok(())
     }
 
-    fn remove_packages(packages: &BTreeSet<String>, _: bool, _: &Config) -> Result<()> {
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        let executable = config.backends.Rustup.executable.as_str();
+
         if !packages.is_empty() {
             for toolchain in packages.iter() {
                 run_command(
-                    ["rustup", "toolchain", "remove", toolchain.as_str()],
+                    [executable, "toolchain", "remove"]
+                        .into_iter()
+                        .chain([toolchain.as_str()]),
                     Perms::Same,
                 )?;
             }
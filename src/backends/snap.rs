@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Snap;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SnapQueryInfo {
+    /// the channel the snap is tracking, such as "latest/stable"
+    pub tracking: String,
+}
+
+#[serde_inline_default]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct SnapInstallOptions {
+    /// the channel to install the snap from, such as "latest/edge"
+    channel: Option<String>,
+    /// whether to install the snap with classic confinement
+    #[serde_inline_default(SnapInstallOptions::default().classic)]
+    classic: bool,
+}
+impl MergeInstallOptions for SnapInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = SnapInstallOptions {
+            channel: merge_optionals("channel", self.channel, other.channel, &mut conflicts),
+            classic: self.classic || other.classic,
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SnapConfig {
+    #[serde_inline_default(SnapConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(SnapConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(SnapConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for SnapConfig {
+    fn default() -> Self {
+        SnapConfig {
+            enabled: true,
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// The notes `snap list` gives snaps which are part of the snap system
+/// itself rather than installed by the user.
+const SYSTEM_SNAP_NOTES: [&str; 3] = ["base", "core", "snapd"];
+
+impl Backend for Snap {
+    type QueryInfo = SnapQueryInfo;
+    type InstallOptions = SnapInstallOptions;
+    type Config = SnapConfig;
+
+    fn is_available(_: &Config) -> bool {
+        command_found("snap")
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let stdout = run_command_for_stdout(["snap", "list"], Perms::Same)?;
+
+        Ok(extract_packages(&stdout))
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        // snap only allows installing multiple snaps at once without options
+        let (plain, with_options): (Vec<_>, Vec<_>) = packages
+            .iter()
+            .partition(|(_, options)| **options == SnapInstallOptions::default());
+
+        if !plain.is_empty() {
+            run_command(
+                ["snap", "install"]
+                    .into_iter()
+                    .chain(config.backends.Snap.extra_args.iter().map(String::as_str))
+                    .chain(plain.iter().map(|(package, _)| package.as_str())),
+                Perms::Sudo,
+            )?;
+        }
+
+        for (package, options) in with_options {
+            run_command(
+                ["snap", "install"]
+                    .into_iter()
+                    .chain(Some("--channel").filter(|_| options.channel.is_some()))
+                    .chain(options.channel.as_deref())
+                    .chain(Some("--classic").filter(|_| options.classic))
+                    .chain(config.backends.Snap.extra_args.iter().map(String::as_str))
+                    .chain([package.as_str()]),
+                Perms::Sudo,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, _: &Config) -> Result<()> {
+        if !packages.is_empty() {
+            run_command(
+                ["snap", "remove"]
+                    .into_iter()
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Sudo,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the snaps from the columns of `snap list`, skipping the snaps
+/// which are part of the snap system itself, such as:
+///
+/// ```text
+/// Name    Version   Rev    Tracking       Publisher   Notes
+/// core22  20240111  1122   latest/stable  canonical✓  base
+/// firefox 124.0-2   4033   latest/stable  mozilla✓    -
+/// snapd   2.61.2    21184  latest/stable  canonical✓  snapd
+/// ```
+fn extract_packages(stdout: &str) -> BTreeMap<String, SnapQueryInfo> {
+    stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+
+            let name = columns.first()?;
+            let tracking = columns.get(3).unwrap_or(&"");
+            let notes = columns.get(5).unwrap_or(&"");
+
+            if notes
+                .split(',')
+                .any(|note| SYSTEM_SNAP_NOTES.contains(&note))
+            {
+                return None;
+            }
+
+            Some((
+                name.to_string(),
+                SnapQueryInfo {
+                    tracking: tracking.to_string(),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_snap_list_packages() {
+        let stdout = "\
+Name     Version   Rev    Tracking       Publisher   Notes
+core22   20240111  1122   latest/stable  canonical✓  base
+firefox  124.0-2   4033   latest/stable  mozilla✓    -
+code     e170252f  159    latest/edge    vscode✓     classic
+snapd    2.61.2    21184  latest/stable  canonical✓  snapd
+";
+
+        let packages = extract_packages(stdout);
+
+        assert_eq!(packages.keys().collect::<Vec<_>>(), ["code", "firefox"]);
+        assert_eq!(packages["code"].tracking, "latest/edge");
+        assert_eq!(packages["firefox"].tracking, "latest/stable");
+    }
+}
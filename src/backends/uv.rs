@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use color_eyre::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::command_found;
+use crate::cmd::run_command;
+use crate::cmd::run_command_for_stdout;
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Uv;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UvQueryInfo {
+    pub version: String,
+}
+
+#[serde_inline_default]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct UvInstallOptions {
+    /// the python version to install the tool with, such as "3.12"
+    python: Option<String>,
+    /// extra packages to install alongside the tool
+    #[serde_inline_default(UvInstallOptions::default().with)]
+    with: Vec<String>,
+    /// a source to install the tool from instead of the package index, such
+    /// as "git+https://github.com/psf/black" or a local path
+    from: Option<String>,
+    /// whether to install the tool from a local path in editable mode
+    #[serde_inline_default(UvInstallOptions::default().editable)]
+    editable: bool,
+}
+impl MergeInstallOptions for UvInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = UvInstallOptions {
+            python: merge_optionals("python", self.python, other.python, &mut conflicts),
+            with: merge_lists(self.with, other.with),
+            from: merge_optionals("from", self.from, other.from, &mut conflicts),
+            editable: self.editable || other.editable,
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UvConfig {
+    #[serde_inline_default(UvConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(UvConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(UvConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(UvConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for UvConfig {
+    fn default() -> Self {
+        UvConfig {
+            enabled: true,
+            executable: "uv".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl Backend for Uv {
+    type QueryInfo = UvQueryInfo;
+    type InstallOptions = UvInstallOptions;
+    type Config = UvConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Uv.executable)
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let stdout = run_command_for_stdout(
+            [config.backends.Uv.executable.as_str(), "tool", "list"],
+            Perms::Same,
+        )?;
+
+        Ok(extract_packages(&stdout))
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        _: bool,
+        config: &Config,
+    ) -> Result<()> {
+        for (package, options) in packages {
+            run_command(
+                [config.backends.Uv.executable.as_str(), "tool", "install"]
+                    .into_iter()
+                    .chain(config.backends.Uv.extra_args.iter().map(String::as_str))
+                    .chain(Some("--python").filter(|_| options.python.is_some()))
+                    .chain(options.python.as_deref())
+                    .chain(options.with.iter().flat_map(|x| ["--with", x.as_str()]))
+                    .chain(Some("--from").filter(|_| options.from.is_some()))
+                    .chain(options.from.as_deref())
+                    .chain(Some("--editable").filter(|_| options.editable))
+                    .chain([package.as_str()]),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(packages: &BTreeSet<String>, _: bool, config: &Config) -> Result<()> {
+        if !packages.is_empty() {
+            run_command(
+                [config.backends.Uv.executable.as_str(), "tool", "uninstall"]
+                    .into_iter()
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Same,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the tools from `uv tool list`, which lists each tool and its
+/// version followed by its executables, such as:
+///
+/// ```text
+/// black v24.2.0
+/// - black
+/// - blackd
+/// ```
+fn extract_packages(stdout: &str) -> BTreeMap<String, UvQueryInfo> {
+    stdout
+        .lines()
+        .filter(|line| !line.starts_with('-'))
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let name = words.next()?;
+            let version = words.next()?.strip_prefix('v')?;
+
+            Some((
+                name.to_string(),
+                UvQueryInfo {
+                    version: version.to_string(),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_uv_tool_list_packages() {
+        let stdout = "\
+black v24.2.0
+- black
+- blackd
+ruff v0.3.0
+- ruff
+";
+
+        let packages = extract_packages(stdout);
+
+        assert_eq!(packages.keys().collect::<Vec<_>>(), ["black", "ruff"]);
+        assert_eq!(packages["black"].version, "24.2.0");
+        assert_eq!(packages["ruff"].version, "0.3.0");
+    }
+}
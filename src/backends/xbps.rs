@@ -4,6 +4,7 @@ use std::process::Command;
 use color_eyre::Result;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
 
 use crate::cmd::{command_found, run_command, run_command_for_stdout};
 use crate::prelude::*;
@@ -14,12 +15,54 @@ pub struct Xbps;
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct XbpsQueryInfo {}
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct XbpsInstallOptions {}
+impl MergeInstallOptions for XbpsInstallOptions {
+    fn merge(self, _: Self) -> Result<Self, Vec<String>> {
+        Ok(self)
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct XbpsConfig {
+    #[serde_inline_default(XbpsConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(XbpsConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(XbpsConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for XbpsConfig {
+    fn default() -> Self {
+        XbpsConfig {
+            enabled: true,
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
 
 impl Backend for Xbps {
     type QueryInfo = XbpsQueryInfo;
     type InstallOptions = XbpsInstallOptions;
+    type Config = XbpsConfig;
+
+    fn is_available(_: &Config) -> bool {
+        command_found("xbps-query")
+    }
 
     fn map_managed_packages(
         packages: BTreeMap<String, Self::InstallOptions>,
@@ -29,9 +72,9 @@ impl Backend for Xbps {
     }
 
     fn query_installed_packages(
-        _: &Config,
+        config: &Config,
     ) -> Result<std::collections::BTreeMap<String, Self::QueryInfo>> {
-        if !command_found("xbps-query") {
+        if !Self::is_available(config) {
             return Ok(BTreeMap::new());
         }
 
@@ -62,13 +105,14 @@ impl Backend for Xbps {
     fn install_packages(
         packages: &std::collections::BTreeMap<String, Self::InstallOptions>,
         no_confirm: bool,
-        _: &Config,
+        config: &Config,
     ) -> Result<()> {
         if !packages.is_empty() {
             run_command(
                 ["xbps-install", "-S"]
                     .into_iter()
                     .chain(Some("-y").filter(|_| no_confirm))
+                    .chain(config.backends.Xbps.extra_args.iter().map(String::as_str))
                     .chain(packages.keys().map(String::as_str)),
                 Perms::Sudo,
             )?;
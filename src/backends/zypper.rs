@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::cmd::{command_found, run_command, run_command_for_stdout};
+use crate::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct Zypper;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ZypperQueryInfo {}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct ZypperInstallOptions {
+    /// the alias of the repository to install the package from
+    repo: Option<String>,
+}
+impl MergeInstallOptions for ZypperInstallOptions {
+    fn merge(self, other: Self) -> Result<Self, Vec<String>> {
+        let mut conflicts = Vec::new();
+
+        let merged = ZypperInstallOptions {
+            repo: merge_optionals("repo", self.repo, other.repo, &mut conflicts),
+        };
+
+        if conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ZypperConfig {
+    #[serde_inline_default(ZypperConfig::default().enabled)]
+    pub enabled: bool,
+    #[serde_inline_default(ZypperConfig::default().executable)]
+    pub executable: String,
+    #[serde_inline_default(ZypperConfig::default().extra_args)]
+    pub extra_args: Vec<String>,
+    #[serde_inline_default(ZypperConfig::default().ignore)]
+    pub ignore: Vec<String>,
+}
+impl Default for ZypperConfig {
+    fn default() -> Self {
+        ZypperConfig {
+            enabled: true,
+            executable: "zypper".to_string(),
+            extra_args: Vec::new(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// The file in which libzypp records the packages which were only installed
+/// to satisfy the dependencies of other packages.
+const AUTO_INSTALLED_PATH: &str = "/var/lib/zypp/AutoInstalled";
+
+impl Backend for Zypper {
+    type QueryInfo = ZypperQueryInfo;
+    type InstallOptions = ZypperInstallOptions;
+    type Config = ZypperConfig;
+
+    fn is_available(config: &Config) -> bool {
+        command_found(&config.backends.Zypper.executable)
+    }
+
+    fn map_managed_packages(
+        packages: BTreeMap<String, Self::InstallOptions>,
+        _: &Config,
+    ) -> Result<BTreeMap<String, Self::InstallOptions>> {
+        Ok(packages)
+    }
+
+    fn query_installed_packages(config: &Config) -> Result<BTreeMap<String, Self::QueryInfo>> {
+        if !Self::is_available(config) {
+            return Ok(BTreeMap::new());
+        }
+
+        let stdout = run_command_for_stdout(
+            [
+                config.backends.Zypper.executable.as_str(),
+                "--xmlout",
+                "search",
+                "--installed-only",
+                "--type",
+                "package",
+            ],
+            Perms::Same,
+        )?;
+
+        // zypper has no flag for listing only the user installed packages so
+        // subtract the auto installed packages instead
+        let auto_installed = match std::fs::read_to_string(AUTO_INSTALLED_PATH) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(error) => {
+                return Err(error).wrap_err(eyre!("reading {AUTO_INSTALLED_PATH:?}"));
+            }
+        };
+        let auto_installed = extract_auto_installed(&auto_installed);
+
+        Ok(extract_packages(&stdout)
+            .into_iter()
+            .filter(|package| !auto_installed.contains(package.as_str()))
+            .map(|package| (package, ZypperQueryInfo {}))
+            .collect())
+    }
+
+    fn install_packages(
+        packages: &BTreeMap<String, Self::InstallOptions>,
+        no_confirm: bool,
+        config: &Config,
+    ) -> Result<()> {
+        // --from applies to every package in the command so install the
+        // packages of each repository separately
+        let mut by_repo: BTreeMap<Option<&str>, Vec<&str>> = BTreeMap::new();
+        for (package, options) in packages {
+            by_repo
+                .entry(options.repo.as_deref())
+                .or_default()
+                .push(package.as_str());
+        }
+
+        for (repo, packages) in by_repo {
+            run_command(
+                [config.backends.Zypper.executable.as_str()]
+                    .into_iter()
+                    .chain(Some("--non-interactive").filter(|_| no_confirm))
+                    .chain(["install"])
+                    .chain(Some("--from").filter(|_| repo.is_some()))
+                    .chain(repo)
+                    .chain(config.backends.Zypper.extra_args.iter().map(String::as_str))
+                    .chain(packages),
+                Perms::Sudo,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_packages(
+        packages: &BTreeSet<String>,
+        no_confirm: bool,
+        config: &Config,
+    ) -> Result<()> {
+        if !packages.is_empty() {
+            run_command(
+                [config.backends.Zypper.executable.as_str()]
+                    .into_iter()
+                    .chain(Some("--non-interactive").filter(|_| no_confirm))
+                    .chain(["remove"])
+                    .chain(packages.iter().map(String::as_str)),
+                Perms::Sudo,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the package names from `zypper --xmlout search`, such as:
+///
+/// ```text
+/// <?xml version='1.0'?>
+/// <stream>
+/// <search-result version="0.0">
+/// <solvable-list>
+/// <solvable status="installed" name="bash" summary="The GNU Bourne-Again Shell" kind="package"/>
+/// </solvable-list>
+/// </search-result>
+/// </stream>
+/// ```
+fn extract_packages(stdout: &str) -> BTreeSet<String> {
+    let regex = Regex::new(r#"<solvable\s[^>]*\bname="([^"]+)""#).expect("valid regex");
+
+    regex
+        .captures_iter(stdout)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// Extracts the package names from the auto installed file, which lists one
+/// package per line after some comments, such as:
+///
+/// ```text
+/// # AutoInstalled (generated by libzypp)
+/// glibc-locale
+/// libgcc_s1
+/// ```
+fn extract_auto_installed(contents: &str) -> BTreeSet<&str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_zypper_search_packages() {
+        let stdout = r#"<?xml version='1.0'?>
+<stream>
+<message type="info">Loading repository data...</message>
+<search-result version="0.0">
+<solvable-list>
+<solvable status="installed" name="bash" summary="The GNU Bourne-Again Shell" kind="package"/>
+<solvable status="installed" name="glibc-locale" summary="Locale Data" kind="package"/>
+</solvable-list>
+</search-result>
+</stream>"#;
+
+        assert_eq!(
+            extract_packages(stdout).into_iter().collect::<Vec<_>>(),
+            ["bash", "glibc-locale"]
+        );
+    }
+
+    #[test]
+    fn extract_zypper_auto_installed() {
+        let contents = "\
+# AutoInstalled (generated by libzypp)
+# 2024-03-01 12:00:00
+
+glibc-locale
+libgcc_s1
+";
+
+        assert_eq!(
+            extract_auto_installed(contents)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["glibc-locale", "libgcc_s1"]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! The clap declarative command line interface
 
 use crate::prelude::*;
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -13,12 +13,16 @@ use std::path::PathBuf;
     subcommand_required(true)
 )]
 pub struct MainArguments {
-    #[arg(short = 'n', long)]
+    #[arg(short = 'n', long, env = "METAPAC_HOSTNAME")]
     /// specify a different hostname
     pub hostname: Option<String>,
-    #[arg(short, long)]
+    #[arg(short, long, env = "METAPAC_CONFIG_DIR")]
     /// specify a different config directory
     pub config_dir: Option<PathBuf>,
+    #[arg(short, long, env = "METAPAC_PROFILE", value_delimiter = ',')]
+    /// use the groups from a profile in the config in addition to the
+    /// hostname's groups, can be given multiple times
+    pub profile: Vec<String>,
     #[command(subcommand)]
     pub subcommand: MainSubcommand,
 }
@@ -27,7 +31,9 @@ pub struct MainArguments {
 pub enum MainSubcommand {
     Clean(CleanCommand),
     Add(AddCommand),
+    Import(ImportCommand),
     Review(ReviewCommand),
+    Schema(SchemaCommand),
     Sync(SyncCommand),
     Unmanaged(UnmanagedCommand),
 }
@@ -58,11 +64,50 @@ pub struct AddCommand {
     pub group: String,
 }
 
+#[derive(Args)]
+/// import group files from another declarative package manager
+///
+/// existing group files are never overwritten
+pub struct ImportCommand {
+    #[command(subcommand)]
+    pub source: ImportSource,
+}
+
+#[derive(Subcommand)]
+pub enum ImportSource {
+    Pacdef(ImportPacdefCommand),
+}
+
+#[derive(Args)]
+/// import pacdef group files into metapac group files of the same name
+pub struct ImportPacdefCommand {
+    /// the pacdef groups directory, usually ~/.config/pacdef/groups/
+    pub dir: PathBuf,
+}
+
 #[derive(Args)]
 #[command(visible_alias("r"))]
 /// review unmanaged packages
 pub struct ReviewCommand {}
 
+#[derive(Args)]
+/// print the JSON schema of config or group files
+///
+/// this can be used by editors with a TOML language server to validate
+/// and auto-complete the files
+pub struct SchemaCommand {
+    /// which kind of file to print the JSON schema of
+    pub file: SchemaFile,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SchemaFile {
+    /// the config.toml file
+    Config,
+    /// group files
+    Group,
+}
+
 #[derive(Args)]
 #[command(visible_alias("s"))]
 /// install packages from groups
@@ -2,6 +2,7 @@ use std::process::{Command, Stdio};
 
 use color_eyre::{eyre::eyre, Result};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 pub fn command_found(command: &str) -> bool {
     if let Ok(path) = std::env::var("PATH") {
@@ -15,9 +16,11 @@ pub fn command_found(command: &str) -> bool {
     false
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum Perms {
     Sudo,
+    #[default]
     Same,
 }
 
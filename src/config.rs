@@ -1,79 +1,302 @@
-use color_eyre::eyre::Context;
+use color_eyre::eyre::{eyre, Context};
 use color_eyre::Result;
 use serde::{Deserialize, Serialize};
 use serde_inline_default::serde_inline_default;
 use std::{collections::BTreeMap, path::Path};
+use toml::{Table, Value};
+use toml_edit::{ImDocument, Item};
 
+use crate::backends::custom;
 use crate::prelude::*;
+use crate::unknown_keys::{struct_fields, KeyChecker};
 
 // Update README if fields change.
 #[serde_inline_default]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct Config {
-    #[serde_inline_default(Config::default().arch_package_manager)]
-    pub arch_package_manager: ArchPackageManager,
-    #[serde_inline_default(Config::default().flatpak_systemwide)]
-    pub flatpak_systemwide: bool,
     #[serde_inline_default(Config::default().disabled_backends)]
     pub disabled_backends: Vec<String>,
     #[serde_inline_default(Config::default().hostname_groups_enabled)]
     pub hostname_groups_enabled: bool,
     #[serde_inline_default(Config::default().hostname_groups)]
     pub hostname_groups: BTreeMap<String, Vec<String>>,
-}
-impl Default for Config {
-    fn default() -> Self {
-        Config {
-            arch_package_manager: ArchPackageManager::default(),
-            flatpak_systemwide: true,
-            disabled_backends: Vec::new(),
-            hostname_groups_enabled: false,
-            hostname_groups: BTreeMap::new(),
-        }
-    }
+    #[serde_inline_default(Config::default().variables)]
+    pub variables: BTreeMap<String, String>,
+    #[serde_inline_default(Config::default().hostname_variables)]
+    pub hostname_variables: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde_inline_default(Config::default().profiles)]
+    pub profiles: BTreeMap<String, Vec<String>>,
+    #[serde_inline_default(Config::default().group_conditions)]
+    pub group_conditions: BTreeMap<String, GroupConditions>,
+    #[serde_inline_default(Config::default().backends)]
+    pub backends: BackendConfigs,
+    #[serde_inline_default(Config::default().custom_backends)]
+    pub custom_backends: BTreeMap<String, CustomBackendDefinition>,
+    #[serde_inline_default(Config::default().hooks)]
+    pub hooks: Hooks,
 }
 
 impl Config {
     pub fn load(config_dir: &Path) -> Result<Self> {
         let config_file_path = config_dir.join("config.toml");
 
-        if !config_file_path.is_file() {
+        let mut table = if !config_file_path.is_file() {
             log::trace!(
                 "no config file found at {config_file_path:?}, using default config instead"
             );
 
-            Ok(Self::default())
+            Table::new()
         } else {
-            toml::from_str(
-                &std::fs::read_to_string(config_file_path).wrap_err("reading config file")?,
-            )
-            .wrap_err("parsing toml config")
-        }
+            let contents =
+                std::fs::read_to_string(&config_file_path).wrap_err("reading config file")?;
+
+            check_keys(&config_file_path, &contents)?;
+
+            let mut table = toml::from_str::<Table>(&contents).wrap_err("parsing toml config")?;
+
+            migrate_legacy_keys(&mut table);
+
+            table
+        };
+
+        apply_env_overrides(&mut table).wrap_err("applying environment variable overrides")?;
+
+        let mut config: Config = table.try_into().wrap_err("parsing toml config")?;
+
+        config.backends.Custom.ignore = custom::ignore_patterns(&config.custom_backends);
+
+        Ok(config)
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ArchPackageManager {
-    #[default]
-    Pacman,
-    Paru,
-    Yay,
+/// Conditions on the local machine's [`Facts`] under which a group is
+/// selected automatically.
+#[serde_inline_default]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GroupConditions {
+    /// matches if any of these is the `os-release` `ID` or in its `ID_LIKE`
+    #[serde_inline_default(GroupConditions::default().os_id)]
+    pub os_id: Vec<String>,
+    /// matches if any of these is the CPU architecture
+    #[serde_inline_default(GroupConditions::default().arch)]
+    pub arch: Vec<String>,
+    /// matches if all of these backends are available
+    #[serde_inline_default(GroupConditions::default().backends)]
+    pub backends: Vec<String>,
+}
+
+impl GroupConditions {
+    pub fn is_satisfied(&self, facts: &Facts) -> bool {
+        let os_id = self.os_id.is_empty()
+            || self
+                .os_id
+                .iter()
+                .any(|x| facts.os_id.as_ref() == Some(x) || facts.os_id_like.contains(x));
+        let arch = self.arch.is_empty() || self.arch.contains(&facts.arch);
+        let backends = self.backends.iter().all(|x| {
+            facts
+                .backends
+                .iter()
+                .any(|backend| backend.to_string().to_lowercase() == x.to_lowercase())
+        });
+
+        os_id && arch && backends
+    }
 }
-impl ArchPackageManager {
-    pub fn as_command(&self) -> &'static str {
-        match self {
-            ArchPackageManager::Pacman => "pacman",
-            ArchPackageManager::Paru => "paru",
-            ArchPackageManager::Yay => "yay",
+
+/// Flat backend keys from before the `[backends]` table existed as
+/// `(legacy_key, backend, key)`.
+const LEGACY_KEYS: [(&str, &str, &str); 2] = [
+    ("arch_package_manager", "arch", "package_manager"),
+    ("flatpak_systemwide", "flatpak", "systemwide"),
+];
+
+fn check_keys(config_file_path: &Path, contents: &str) -> Result<()> {
+    let document = ImDocument::parse(contents).wrap_err("parsing toml config")?;
+
+    let mut checker = KeyChecker::new(config_file_path, contents);
+
+    let known_keys = struct_fields::<Config>()
+        .iter()
+        .copied()
+        .chain(LEGACY_KEYS.iter().map(|(legacy_key, _, _)| *legacy_key))
+        .collect::<Vec<_>>();
+    checker.check_table(document.as_table(), &known_keys, "the config file");
+
+    if let Some(group_conditions) = document
+        .get("group_conditions")
+        .and_then(Item::as_table_like)
+    {
+        for (group_name, conditions) in group_conditions.iter() {
+            if let Some(conditions) = conditions.as_table_like() {
+                checker.check_table(
+                    conditions,
+                    struct_fields::<GroupConditions>(),
+                    &format!("the {group_name:?} entry of the [group_conditions] config table"),
+                );
+            }
         }
     }
 
-    pub fn change_perms(&self) -> Perms {
-        match self {
-            ArchPackageManager::Pacman => Perms::Sudo,
-            ArchPackageManager::Paru => Perms::Same,
-            ArchPackageManager::Yay => Perms::Same,
+    if let Some(hooks) = document.get("hooks").and_then(Item::as_table_like) {
+        checker.check_table(hooks, struct_fields::<Hooks>(), "the [hooks] config table");
+    }
+
+    if let Some(custom_backends) = document
+        .get("custom_backends")
+        .and_then(Item::as_table_like)
+    {
+        for (name, definition) in custom_backends.iter() {
+            if let Some(definition) = definition.as_table_like() {
+                checker.check_table(
+                    definition,
+                    struct_fields::<CustomBackendDefinition>(),
+                    &format!("the [custom_backends.{name}] config table"),
+                );
+            }
         }
     }
+
+    if let Some(backends) = document.get("backends").and_then(Item::as_table_like) {
+        BackendConfigs::check_keys(backends, &mut checker);
+    }
+
+    checker.finish().wrap_err("checking config file keys")
+}
+
+/// Moves the flat backend keys from before the `[backends]` table existed
+/// into their new locations, without overriding any values already set in
+/// the `[backends]` table.
+fn migrate_legacy_keys(table: &mut Table) {
+    for (legacy_key, backend, key) in LEGACY_KEYS {
+        let Some(value) = table.remove(legacy_key) else {
+            continue;
+        };
+
+        log::warn!("the {legacy_key:?} config key is deprecated, use the {key:?} key in the [backends.{backend}] config table instead");
+
+        let Some(backend_table) = table_at_path(table, &["backends", backend]) else {
+            // let deserialization report the malformed table
+            continue;
+        };
+
+        if backend_table.contains_key(key) {
+            log::warn!("ignoring the deprecated {legacy_key:?} config key as {key:?} is already set in the [backends.{backend}] config table");
+        } else {
+            backend_table.insert(key.to_string(), value);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum EnvValueKind {
+    String,
+    Bool,
+    /// comma separated
+    List,
+}
+
+/// Environment variables which override config keys, take precedence over the
+/// config file and are themselves overridden by cli options.
+const ENV_OVERRIDES: [(&str, &[&str], EnvValueKind); 4] = [
+    (
+        "METAPAC_DISABLED_BACKENDS",
+        &["disabled_backends"],
+        EnvValueKind::List,
+    ),
+    (
+        "METAPAC_HOSTNAME_GROUPS_ENABLED",
+        &["hostname_groups_enabled"],
+        EnvValueKind::Bool,
+    ),
+    (
+        "METAPAC_ARCH_PACKAGE_MANAGER",
+        &["backends", "arch", "package_manager"],
+        EnvValueKind::String,
+    ),
+    (
+        "METAPAC_FLATPAK_SYSTEMWIDE",
+        &["backends", "flatpak", "systemwide"],
+        EnvValueKind::Bool,
+    ),
+];
+
+fn apply_env_overrides(table: &mut Table) -> Result<()> {
+    for (env_var, path, kind) in ENV_OVERRIDES {
+        let Ok(env_value) = std::env::var(env_var) else {
+            continue;
+        };
+
+        log::trace!("overriding config key {path:?} with the {env_var} environment variable");
+
+        let value = match kind {
+            EnvValueKind::String => Value::String(env_value),
+            EnvValueKind::Bool => Value::Boolean(
+                env_value
+                    .trim()
+                    .parse()
+                    .wrap_err(eyre!("parsing {env_var}={env_value:?} as a boolean"))?,
+            ),
+            EnvValueKind::List => Value::Array(
+                env_value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty())
+                    .map(|x| Value::String(x.to_string()))
+                    .collect(),
+            ),
+        };
+
+        let (key, parents) = path.split_last().expect("non-empty config key path");
+        table_at_path(table, parents)
+            .ok_or(eyre!("the config key {parents:?} is not a table"))?
+            .insert(key.to_string(), value);
+    }
+
+    Ok(())
+}
+
+/// Gets the nested table at the given path, creating any missing tables along
+/// the way. Keys are matched case-insensitively like the backend names in the
+/// `[backends]` table, so `["backends", "arch"]` finds a `[backends.Arch]`
+/// table rather than adding a second one beside it.
+fn table_at_path<'a>(table: &'a mut Table, path: &[&str]) -> Option<&'a mut Table> {
+    path.iter().try_fold(table, |table, key| {
+        let key = table
+            .keys()
+            .find(|x| x.to_lowercase() == key.to_lowercase())
+            .cloned()
+            .unwrap_or(key.to_string());
+
+        table
+            .entry(key)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_legacy_keys_into_capitalized_backend_table() {
+        let mut table = toml::from_str::<Table>(
+            r#"
+arch_package_manager = "paru"
+
+[backends.Arch]
+extra_args = ["--needed"]
+"#,
+        )
+        .unwrap();
+
+        migrate_legacy_keys(&mut table);
+
+        let config: Config = table.try_into().unwrap();
+        assert_eq!(config.backends.Arch.extra_args, ["--needed"]);
+        assert_eq!(config.backends.Arch.package_manager.as_command(), "paru");
+    }
 }
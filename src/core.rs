@@ -6,11 +6,19 @@ use color_eyre::Result;
 use dialoguer::Confirm;
 use toml_edit::{Array, DocumentMut, Item, Value};
 
+use crate::hooks::run_hooks;
+use crate::import::import_pacdef;
 use crate::prelude::*;
 use crate::review::review;
 
 impl MainArguments {
     pub fn run(self) -> Result<()> {
+        // the schema doesn't depend on the config or group files so don't
+        // fail on errors in them
+        if let MainSubcommand::Schema(schema) = self.subcommand {
+            return schema.run();
+        }
+
         let hostname = if let Some(x) = self.hostname {
             x
         } else {
@@ -29,25 +37,35 @@ impl MainArguments {
 
         let group_dir = config_dir.join("groups/");
 
+        // importing writes new group files so don't fail on errors in the
+        // existing ones
+        if let MainSubcommand::Import(import) = self.subcommand {
+            return import.run(&group_dir);
+        }
+
         let config = Config::load(&config_dir).wrap_err("loading config file")?;
-        let groups = Groups::load(&group_dir, &hostname, &config)
+        let facts = Facts::gather(&config);
+        let groups = Groups::load(&group_dir, &hostname, &self.profile, &config, &facts)
             .wrap_err("failed to load package install options from groups")?;
 
-        let managed = groups.to_install_options().map_install_packages(&config)?;
+        let managed = groups.to_install_options()?.map_install_packages(&config)?;
+        let ignored = groups.ignored(&config);
 
         match self.subcommand {
-            MainSubcommand::Clean(clean) => clean.run(&managed, &config),
+            MainSubcommand::Clean(clean) => clean.run(&managed, &ignored, &config),
             MainSubcommand::Add(add) => add.run(&group_dir, &groups),
+            MainSubcommand::Import(_) => unreachable!("handled before loading the config"),
             MainSubcommand::Review(review) => review.run(&managed, &config),
-            MainSubcommand::Sync(sync) => sync.run(&managed, &config),
-            MainSubcommand::Unmanaged(unmanaged) => unmanaged.run(&managed, &config),
+            MainSubcommand::Schema(_) => unreachable!("handled before loading the config"),
+            MainSubcommand::Sync(sync) => sync.run(&managed, &groups, &config),
+            MainSubcommand::Unmanaged(unmanaged) => unmanaged.run(&managed, &ignored, &config),
         }
     }
 }
 
 impl CleanCommand {
-    fn run(self, managed: &InstallOptions, config: &Config) -> Result<()> {
-        let unmanaged = unmanaged(managed, config)?;
+    fn run(self, managed: &InstallOptions, ignored: &PackageIds, config: &Config) -> Result<()> {
+        let unmanaged = unmanaged(managed, ignored, config)?;
 
         if unmanaged.is_empty() {
             log::info!("nothing to do since there are no unmanaged packages");
@@ -56,25 +74,27 @@ impl CleanCommand {
 
         if self.no_confirm {
             log::info!("proceeding without confirmation");
-
-            unmanaged.remove_packages(self.no_confirm, config)
         } else {
             println!("{unmanaged}");
 
             println!("these packages will be removed\n");
 
-            if Confirm::new()
+            if !Confirm::new()
                 .with_prompt("do you want to continue?")
                 .default(true)
                 .show_default(true)
                 .interact()
                 .wrap_err("getting user confirmation")?
             {
-                unmanaged.remove_packages(self.no_confirm, config)
-            } else {
-                Ok(())
+                return Ok(());
             }
         }
+
+        run_hooks("pre_clean", &config.hooks.pre_clean, &unmanaged, config)?;
+
+        unmanaged.remove_packages(self.no_confirm, config)?;
+
+        run_hooks("post_clean", &config.hooks.post_clean, &unmanaged, config)
     }
 }
 
@@ -85,7 +105,7 @@ impl AddCommand {
             log::info!("the {} package for the {} backend is already installed in the {containing_group_files:?} group files", self.package, self.backend);
         }
 
-        let group_file = group_dir.join(&self.group).with_extension("toml");
+        let group_file = group_file_path(group_dir, &self.group);
 
         log::info!("parsing group file: {}@{group_file:?}", &self.group);
 
@@ -99,36 +119,93 @@ impl AddCommand {
         let file_contents = read_to_string(&group_file)
             .wrap_err(eyre!("reading group file {}@{group_file:?}", &self.group))?;
 
-        let mut doc = file_contents
-            .parse::<DocumentMut>()
-            .wrap_err(eyre!("parsing group file {}@{group_file:?}", &self.group))?;
-
-        doc.entry(&self.backend.to_string().to_lowercase())
-            .or_insert(Item::Value(Value::Array(Array::from_iter([self
-                .package
-                .clone()]))))
-            .as_array_mut()
-            .wrap_err(eyre!(
-                "the {} backend in the {group_file:?} group file has a non-array value",
-                self.backend
-            ))?
-            .push(self.package);
-
-        fs::write(group_file, doc.to_string())
+        let backend_key = self.backend.to_string().to_lowercase();
+
+        let new_contents = match GroupFileFormat::from_path(&group_file) {
+            GroupFileFormat::Toml => {
+                let mut doc = file_contents
+                    .parse::<DocumentMut>()
+                    .wrap_err(eyre!("parsing group file {}@{group_file:?}", &self.group))?;
+
+                doc.entry(&backend_key)
+                    .or_insert(Item::Value(Value::Array(Array::from_iter([self
+                        .package
+                        .clone()]))))
+                    .as_array_mut()
+                    .wrap_err(eyre!(
+                        "the {} backend in the {group_file:?} group file has a non-array value",
+                        self.backend
+                    ))?
+                    .push(self.package);
+
+                doc.to_string()
+            }
+            format @ (GroupFileFormat::Yaml | GroupFileFormat::Json) => {
+                // comments and formatting are not preserved for yaml and json
+                // group files
+                let mut value: serde_json::Value = match format {
+                    GroupFileFormat::Yaml => serde_norway::from_str(&file_contents)?,
+                    _ => serde_json::from_str(&file_contents)?,
+                };
+                if value.is_null() {
+                    value = serde_json::Value::Object(serde_json::Map::new());
+                }
+
+                value
+                    .as_object_mut()
+                    .wrap_err(eyre!("the {group_file:?} group file is not a table"))?
+                    .entry(backend_key)
+                    .or_insert(serde_json::Value::Array(Vec::new()))
+                    .as_array_mut()
+                    .wrap_err(eyre!(
+                        "the {} backend in the {group_file:?} group file has a non-array value",
+                        self.backend
+                    ))?
+                    .push(serde_json::Value::String(self.package));
+
+                match format {
+                    GroupFileFormat::Yaml => serde_norway::to_string(&value)?,
+                    _ => serde_json::to_string_pretty(&value)? + "\n",
+                }
+            }
+        };
+
+        fs::write(group_file, new_contents)
             .wrap_err("writing back modified group file {group_file:?}")?;
 
         Ok(())
     }
 }
 
+impl ImportCommand {
+    fn run(self, group_dir: &Path) -> Result<()> {
+        match self.source {
+            ImportSource::Pacdef(pacdef) => import_pacdef(&pacdef.dir, group_dir),
+        }
+    }
+}
+
 impl ReviewCommand {
     fn run(self, _: &InstallOptions, _: &Config) -> Result<()> {
         review()
     }
 }
 
+impl SchemaCommand {
+    fn run(self) -> Result<()> {
+        let schema = match self.file {
+            SchemaFile::Config => schemars::schema_for!(Config),
+            SchemaFile::Group => group_file_schema(),
+        };
+
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+
+        Ok(())
+    }
+}
+
 impl SyncCommand {
-    fn run(self, managed: &InstallOptions, config: &Config) -> Result<()> {
+    fn run(self, managed: &InstallOptions, groups: &Groups, config: &Config) -> Result<()> {
         let missing = missing(managed, config)?;
 
         if missing.is_empty() {
@@ -152,15 +229,31 @@ impl SyncCommand {
             return Ok(());
         }
 
-        missing
-            .to_install_options()
-            .install_packages(self.no_confirm, config)
+        let group_hooks = groups.group_hooks(&missing, config)?;
+        let post_install_hooks = groups.post_install_hooks(&missing, config)?;
+
+        run_hooks("pre_sync", &config.hooks.pre_sync, &missing, config)?;
+        for (hooks, packages) in group_hooks.iter() {
+            run_hooks("pre_sync", &hooks.pre_sync, packages, config)?;
+        }
+
+        managed
+            .filter_package_ids(&missing)
+            .install_packages(self.no_confirm, config)?;
+
+        for (commands, package) in post_install_hooks {
+            run_hooks("post_install", commands, &package, config)?;
+        }
+        for (hooks, packages) in group_hooks.iter() {
+            run_hooks("post_sync", &hooks.post_sync, packages, config)?;
+        }
+        run_hooks("post_sync", &config.hooks.post_sync, &missing, config)
     }
 }
 
 impl UnmanagedCommand {
-    fn run(self, managed: &InstallOptions, config: &Config) -> Result<()> {
-        let unmanaged = unmanaged(managed, config)?;
+    fn run(self, managed: &InstallOptions, ignored: &PackageIds, config: &Config) -> Result<()> {
+        let unmanaged = unmanaged(managed, ignored, config)?;
 
         if unmanaged.is_empty() {
             eprintln!("no unmanaged packages");
@@ -172,9 +265,18 @@ impl UnmanagedCommand {
     }
 }
 
-fn unmanaged(managed: &InstallOptions, config: &Config) -> Result<PackageIds> {
-    QueryInfos::query_installed_packages(config)
-        .map(|x| x.to_package_ids().difference(&managed.to_package_ids()))
+fn unmanaged(
+    managed: &InstallOptions,
+    ignored: &PackageIds,
+    config: &Config,
+) -> Result<PackageIds> {
+    let mut unmanaged = QueryInfos::query_installed_packages(config)?
+        .to_package_ids()
+        .difference(&managed.to_package_ids());
+
+    unmanaged.remove_matching(ignored)?;
+
+    Ok(unmanaged)
 }
 fn missing(managed: &InstallOptions, config: &Config) -> Result<PackageIds> {
     Ok(managed
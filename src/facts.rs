@@ -0,0 +1,51 @@
+//! Facts about the local machine used for conditional group selection
+
+use std::collections::BTreeSet;
+use std::fs::read_to_string;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default)]
+pub struct Facts {
+    /// the `ID` field from `os-release`
+    pub os_id: Option<String>,
+    /// the `ID_LIKE` field from `os-release`
+    pub os_id_like: Vec<String>,
+    /// the CPU architecture, such as `x86_64` or `aarch64`
+    pub arch: String,
+    /// the enabled backends whose package managers are installed
+    pub backends: BTreeSet<AnyBackend>,
+}
+
+impl Facts {
+    pub fn gather(config: &Config) -> Self {
+        let os_release = ["/etc/os-release", "/usr/lib/os-release"]
+            .into_iter()
+            .find_map(|path| read_to_string(path).ok())
+            .unwrap_or_default();
+
+        let os_id = os_release_field(&os_release, "ID");
+        let os_id_like = os_release_field(&os_release, "ID_LIKE")
+            .map(|x| x.split_whitespace().map(String::from).collect())
+            .unwrap_or_default();
+
+        Facts {
+            os_id,
+            os_id_like,
+            arch: std::env::consts::ARCH.to_string(),
+            backends: AnyBackend::ALL
+                .iter()
+                .copied()
+                .filter(|backend| backend.is_available(config))
+                .collect(),
+        }
+    }
+}
+
+fn os_release_field(os_release: &str, field: &str) -> Option<String> {
+    os_release.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+
+        (key.trim() == field).then(|| value.trim().trim_matches(['"', '\'']).to_string())
+    })
+}
@@ -1,24 +1,58 @@
 use crate::prelude::*;
+use crate::unknown_keys::{struct_fields, KeyChecker};
 use color_eyre::{
     eyre::{eyre, Context, ContextCompat},
     Result,
 };
+use itertools::Itertools;
+use regex::Regex;
+use schemars::gen::SchemaSettings;
+use schemars::schema::{
+    ArrayValidation, InstanceType, RootSchema, Schema, SchemaObject, SubschemaValidation,
+};
+use schemars::JsonSchema;
 use toml::{Table, Value};
+use toml_edit::{DocumentMut, ImDocument, Item, TableLike};
 
 use std::{
     collections::BTreeMap,
     fs::read_to_string,
-    ops::AddAssign,
     path::{Path, PathBuf},
+    sync::LazyLock,
 };
 
 #[derive(Debug, Default, derive_more::Deref, derive_more::DerefMut)]
-pub struct Groups(BTreeMap<PathBuf, RawInstallOptions>);
+pub struct Groups {
+    #[deref]
+    #[deref_mut]
+    groups: BTreeMap<PathBuf, RawInstallOptions>,
+    /// the group file which first included each included group file
+    included_by: BTreeMap<PathBuf, PathBuf>,
+    /// packages excluded by any of the group files
+    excludes: PackageIds,
+    /// glob patterns of packages ignored by any of the group files
+    ignored: PackageIds,
+    /// the hooks of each group file
+    hooks: BTreeMap<PathBuf, GroupHooks>,
+    /// the post_install hooks of long-form packages
+    post_install: BTreeMap<(AnyBackend, String), Vec<String>>,
+}
+
+/// The contents of a single group file.
+#[derive(Debug, Default)]
+struct GroupFile {
+    includes: Vec<String>,
+    excludes: PackageIds,
+    ignored: PackageIds,
+    hooks: GroupHooks,
+    post_install: BTreeMap<(AnyBackend, String), Vec<String>>,
+    raw_install_options: RawInstallOptions,
+}
 
 impl Groups {
     pub fn contains(&self, backend: AnyBackend, package: &String) -> Vec<PathBuf> {
         let mut result = Vec::new();
-        for (group_file, raw_install_options) in self.0.iter() {
+        for (group_file, raw_install_options) in self.iter() {
             if raw_install_options
                 .to_raw_package_ids()
                 .contains(backend, package)
@@ -29,64 +63,113 @@ impl Groups {
         result
     }
 
-    pub fn to_install_options(&self) -> InstallOptions {
-        let mut reoriented: BTreeMap<(AnyBackend, String), BTreeMap<PathBuf, u32>> =
-            BTreeMap::new();
+    /// Combines the packages from every group file, merging the install
+    /// options of packages declared in multiple group files and returning an
+    /// error listing every package whose install options conflict.
+    pub fn to_install_options(&self) -> Result<InstallOptions> {
+        let mut install_options = InstallOptions::default();
+        let mut conflicts = Vec::new();
 
-        for (group_file, raw_install_options) in self.iter() {
-            for (backend, package_ids) in raw_install_options.to_raw_package_ids().iter() {
-                for package_id in package_ids {
-                    reoriented
-                        .entry((*backend, package_id.clone()))
-                        .or_default()
-                        .entry(group_file.clone())
-                        .or_default()
-                        .add_assign(1);
-                }
-            }
-        }
+        macro_rules! x {
+            ($($backend:ident),*) => {
+                $(
+                    for (_, raw_install_options) in self.iter() {
+                        for (package_id, options) in raw_install_options.$backend.iter().cloned() {
+                            if self.excludes.contains(AnyBackend::$backend, &package_id) {
+                                continue;
+                            }
 
-        //warn the user about duplicated packages and output a deduplicated InstallOptions
-        for ((backend, package_id), group_files) in reoriented.iter() {
-            if group_files.len() > 1 {
-                log::warn!("duplicate {package_id:?} package in group files: {group_files:?} for the {backend} backend");
-                log::warn!("only one of the duplicated will be used which could may cause unintended behaviour if the duplicates have different install options");
-            }
-        }
+                            let merged = match install_options.$backend.remove(&package_id) {
+                                Some(existing) => match existing.merge(options) {
+                                    Ok(merged) => merged,
+                                    Err(fields) => {
+                                        conflicts.push(self.conflict_report(AnyBackend::$backend, &package_id, &fields));
+                                        continue;
+                                    }
+                                },
+                                None => options,
+                            };
 
-        let mut merged_raw_install_options = RawInstallOptions::default();
-        for mut raw_install_options in self.values().cloned() {
-            merged_raw_install_options.append(&mut raw_install_options);
+                            install_options.$backend.insert(package_id, merged);
+                        }
+                    }
+                )*
+            };
         }
+        apply_public_backends!(x);
 
-        let mut install_options = InstallOptions::default();
-        macro_rules! x {
-                ($($backend:ident),*) => {
-                    $(
-                        install_options.$backend = merged_raw_install_options.$backend.into_iter().collect();
-                    )*
-                };
+        if !conflicts.is_empty() {
+            return Err(eyre!(
+                "conflicting install options for packages declared in multiple group files:\n{}",
+                conflicts.into_iter().unique().join("\n")
+            ));
         }
-        apply_public_backends!(x);
 
-        install_options
+        Ok(install_options)
+    }
+
+    fn conflict_report(
+        &self,
+        backend: AnyBackend,
+        package_id: &String,
+        fields: &[String],
+    ) -> String {
+        let group_files = self
+            .contains(backend, package_id)
+            .iter()
+            .map(|group_file| {
+                self.provenance(group_file)
+                    .iter()
+                    .map(|x| format!("{x:?}"))
+                    .join(" <- ")
+            })
+            .join(", ");
+
+        format!(
+            "the {package_id:?} package for the {backend} backend in group files [{group_files}]: {}",
+            fields.join(", ")
+        )
     }
 
-    pub fn load(group_dir: &Path, hostname: &str, config: &Config) -> Result<Groups> {
+    pub fn load(
+        group_dir: &Path,
+        hostname: &str,
+        profiles: &[String],
+        config: &Config,
+        facts: &Facts,
+    ) -> Result<Groups> {
         if !group_dir.is_dir() {
             log::warn!("the groups directory: {group_dir:?}, was not found, assuming there are no group files. If this was intentional please create an empty groups folder.");
 
             return Ok(Groups::default());
         }
 
+        let profile_group_names = profiles
+            .iter()
+            .map(|profile| {
+                config
+                    .profiles
+                    .get(profile)
+                    .wrap_err(eyre!("no profile named {profile:?} in the profiles config"))
+            })
+            .flatten_ok()
+            .collect::<Result<Vec<_>>>()?;
+
         let group_files = if config.hostname_groups_enabled {
-            let group_names = config.hostname_groups.get(hostname).wrap_err(eyre!(
-                "no hostname entry in the hostname_groups config for the hostname: {hostname}"
-            ))?;
+            let group_names = hostname_group_names(hostname, config)?;
+
+            let conditional_group_names = config
+                .group_conditions
+                .iter()
+                .filter(|(_, conditions)| conditions.is_satisfied(facts))
+                .map(|(group_name, _)| group_name);
 
             group_names
                 .iter()
-                .map(|group_name| group_dir.join(group_name).with_extension("toml"))
+                .chain(conditional_group_names)
+                .chain(profile_group_names)
+                .unique()
+                .map(|group_name| group_file_path(group_dir, group_name))
                 .collect::<Vec<_>>()
         } else {
             walkdir::WalkDir::new(group_dir)
@@ -95,38 +178,587 @@ impl Groups {
                 .filter_map(Result::ok)
                 .filter(|x| !x.file_type().is_dir())
                 .map(|x| x.path().to_path_buf())
+                .filter(|group_file| {
+                    let group_name = group_file
+                        .strip_prefix(group_dir)
+                        .unwrap_or(group_file)
+                        .with_extension("");
+
+                    config
+                        .group_conditions
+                        .get(group_name.to_string_lossy().as_ref())
+                        .is_none_or(|conditions| conditions.is_satisfied(facts))
+                })
+                .chain(
+                    profile_group_names
+                        .into_iter()
+                        .map(|group_name| group_file_path(group_dir, group_name)),
+                )
+                .unique()
                 .collect::<Vec<_>>()
         };
 
+        let variables = hostname_variables(hostname, config)?;
+
         let mut groups = Self::default();
 
         for group_file in group_files {
-            let file_contents =
-                read_to_string(&group_file).wrap_err(eyre!("reading group file {group_file:?}"))?;
+            groups.included_by.remove(&group_file);
+            groups.load_group_file(group_dir, group_file, &variables, &mut Vec::new())?;
+        }
 
-            let raw_install_options = parse_group_file(&group_file, &file_contents)
-                .wrap_err(eyre!("parsing group file {group_file:?}"))?;
+        Ok(groups)
+    }
+
+    /// Loads a group file and, recursively, every group file it includes.
+    ///
+    /// `stack` is the chain of group files currently being included, used to
+    /// detect include cycles.
+    fn load_group_file(
+        &mut self,
+        group_dir: &Path,
+        group_file: PathBuf,
+        variables: &BTreeMap<String, String>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        if let Some(index) = stack.iter().position(|x| x == &group_file) {
+            let cycle = stack[index..]
+                .iter()
+                .chain([&group_file])
+                .collect::<Vec<_>>();
+            return Err(eyre!("group file include cycle: {cycle:?}"));
+        }
 
-            groups.insert(group_file, raw_install_options);
+        if self.contains_key(&group_file) {
+            return Ok(());
         }
 
-        Ok(groups)
+        let file_contents =
+            read_to_string(&group_file).wrap_err(eyre!("reading group file {group_file:?}"))?;
+
+        let mut parsed = parse_group_file(&group_file, &file_contents, variables)
+            .wrap_err(eyre!("parsing group file {group_file:?}"))?;
+
+        stack.push(group_file.clone());
+        for include in std::mem::take(&mut parsed.includes) {
+            let included_file = group_file_path(group_dir, &include);
+
+            if !self.contains_key(&included_file) {
+                self.included_by
+                    .entry(included_file.clone())
+                    .or_insert_with(|| group_file.clone());
+            }
+
+            self.load_group_file(group_dir, included_file, variables, stack)
+                .wrap_err(eyre!("including group file from {group_file:?}"))?;
+        }
+        stack.pop();
+
+        self.excludes.append(&mut parsed.excludes);
+        self.ignored.append(&mut parsed.ignored);
+        for (package, mut commands) in parsed.post_install {
+            self.post_install
+                .entry(package)
+                .or_default()
+                .append(&mut commands);
+        }
+        self.hooks.insert(group_file.clone(), parsed.hooks);
+        self.insert(group_file, parsed.raw_install_options);
+
+        Ok(())
+    }
+
+    /// The glob patterns of packages to ignore from both the config and the
+    /// group files.
+    pub fn ignored(&self, config: &Config) -> PackageIds {
+        let mut ignored = config.backends.ignored();
+        ignored.append(&mut self.ignored.clone());
+        ignored
+    }
+
+    /// The hooks of each group file which declares any of the given packages,
+    /// along with those packages.
+    pub fn group_hooks(
+        &self,
+        packages: &PackageIds,
+        config: &Config,
+    ) -> Result<Vec<(&GroupHooks, PackageIds)>> {
+        let mut result = Vec::new();
+        for (group_file, hooks) in self.hooks.iter() {
+            let declared = self[group_file].to_raw_package_ids().to_package_ids();
+            let affected = self.mapped_package_ids(&declared, packages, config)?;
+
+            if !affected.is_empty() {
+                result.push((hooks, affected));
+            }
+        }
+        Ok(result)
+    }
+
+    /// The post_install hooks of each of the given packages which has any.
+    pub fn post_install_hooks(
+        &self,
+        packages: &PackageIds,
+        config: &Config,
+    ) -> Result<Vec<(&[String], PackageIds)>> {
+        let mut result = Vec::new();
+        for ((backend, package_id), commands) in self.post_install.iter() {
+            let mut declared = PackageIds::default();
+            declared
+                .entry(*backend)
+                .or_default()
+                .insert(package_id.clone());
+            let affected = self.mapped_package_ids(&declared, packages, config)?;
+
+            if !affected.is_empty() {
+                result.push((commands.as_slice(), affected));
+            }
+        }
+        Ok(result)
+    }
+
+    /// The given packages which the declared packages are installed as once
+    /// mapped by their backends, such as the members of arch package groups
+    /// or the full names of tapped brew formulae.
+    fn mapped_package_ids(
+        &self,
+        declared: &PackageIds,
+        packages: &PackageIds,
+        config: &Config,
+    ) -> Result<PackageIds> {
+        Ok(self
+            .to_install_options()?
+            .filter_package_ids(declared)
+            .map_install_packages(config)?
+            .to_package_ids()
+            .intersection(packages))
+    }
+
+    /// The chain of group files which led to the given group file being
+    /// loaded, starting with the group file itself.
+    pub fn provenance(&self, group_file: &Path) -> Vec<PathBuf> {
+        let mut chain = vec![group_file.to_path_buf()];
+        while let Some(included_by) = self.included_by.get(chain.last().expect("non-empty")) {
+            chain.push(included_by.clone());
+        }
+        chain
     }
 }
 
-fn parse_group_file(group_file: &Path, contents: &str) -> Result<RawInstallOptions> {
-    let mut raw_install_options = RawInstallOptions::default();
+const DEFAULT_HOSTNAME_KEY: &str = "*";
+const REGEX_HOSTNAME_KEY_PREFIX: &str = "regex:";
+
+/// The group names from every `hostname_groups` entry whose key matches the
+/// hostname, falling back to the `"*"` entry if no other entry matches.
+fn hostname_group_names(hostname: &str, config: &Config) -> Result<Vec<String>> {
+    let mut matched = false;
+    let mut group_names = Vec::new();
+
+    for (key, names) in config.hostname_groups.iter() {
+        if key != DEFAULT_HOSTNAME_KEY && hostname_matches("hostname_groups", key, hostname)? {
+            matched = true;
+            group_names.extend(names.iter().cloned());
+        }
+    }
+
+    if !matched {
+        group_names = config
+            .hostname_groups
+            .get(DEFAULT_HOSTNAME_KEY)
+            .wrap_err(eyre!(
+                "no hostname entry in the hostname_groups config matches the hostname: {hostname}"
+            ))?
+            .clone();
+    }
+
+    Ok(group_names.into_iter().unique().collect())
+}
+
+/// The variables from the config, overridden by those from every
+/// `hostname_variables` entry whose key matches the hostname.
+fn hostname_variables(hostname: &str, config: &Config) -> Result<BTreeMap<String, String>> {
+    let mut variables = config.variables.clone();
+
+    for (key, hostname_variables) in config.hostname_variables.iter() {
+        if hostname_matches("hostname_variables", key, hostname)? {
+            variables.extend(hostname_variables.clone());
+        }
+    }
+
+    Ok(variables)
+}
+
+/// Whether the key of a hostname keyed config table, such as
+/// `hostname_groups`, matches the hostname.
+fn hostname_matches(table: &str, key: &str, hostname: &str) -> Result<bool> {
+    if let Some(regex) = key.strip_prefix(REGEX_HOSTNAME_KEY_PREFIX) {
+        // anchored so that, like globs, the regex must match the whole
+        // hostname rather than any part of it
+        Ok(Regex::new(&format!("^(?:{regex})$"))
+            .wrap_err(eyre!("parsing the {table} regex: {key:?}"))?
+            .is_match(hostname))
+    } else {
+        Ok(glob::Pattern::new(key)
+            .wrap_err(eyre!("parsing the {table} glob pattern: {key:?}"))?
+            .matches(hostname))
+    }
+}
+
+/// The file formats group files can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupFileFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl GroupFileFormat {
+    pub const ALL: [GroupFileFormat; 3] = [
+        GroupFileFormat::Toml,
+        GroupFileFormat::Yaml,
+        GroupFileFormat::Json,
+    ];
+
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            GroupFileFormat::Toml => &["toml"],
+            GroupFileFormat::Yaml => &["yaml", "yml"],
+            GroupFileFormat::Json => &["json"],
+        }
+    }
+
+    /// Detects the format from the file extension, group files with any
+    /// other extension are assumed to be toml.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&extension.as_str()))
+            .unwrap_or(GroupFileFormat::Toml)
+    }
+
+    pub fn parse(&self, contents: &str) -> Result<Table> {
+        match self {
+            GroupFileFormat::Toml => toml::from_str(contents).wrap_err("parsing toml"),
+            GroupFileFormat::Yaml => serde_norway::from_str(contents).wrap_err("parsing yaml"),
+            GroupFileFormat::Json => serde_json::from_str(contents).wrap_err("parsing json"),
+        }
+    }
+}
 
-    let toml = toml::from_str::<Table>(contents)?;
+/// The path of the group file with the given name, trying each group file
+/// format's extensions in turn and defaulting to toml if none exist.
+pub fn group_file_path(group_dir: &Path, group_name: &str) -> PathBuf {
+    let group_file = group_dir.join(group_name);
+
+    GroupFileFormat::ALL
+        .iter()
+        .flat_map(|format| format.extensions())
+        .map(|extension| group_file.with_extension(extension))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| group_file.with_extension("toml"))
+}
+
+/// The JSON schema of group files, generated from each backend's install
+/// options.
+pub fn group_file_schema() -> RootSchema {
+    let mut gen = SchemaSettings::draft07().into_generator();
+
+    let string_array = gen.subschema_for::<Vec<String>>();
+
+    let mut package_ids = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    };
+    package_ids.object().additional_properties = Some(Box::new(false.into()));
+
+    let mut schema = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    };
+    schema.metadata().title = Some("metapac group file".to_string());
+
+    macro_rules! x {
+        ($($backend:ident),*) => {
+            $(
+                let backend_name = $backend.to_string().to_lowercase();
+
+                let mut long_form =
+                    <<$backend as Backend>::InstallOptions as JsonSchema>::json_schema(&mut gen)
+                        .into_object();
+                let mut package = StringPackageStruct::json_schema(&mut gen).into_object();
+                long_form.object().properties.append(&mut package.object().properties);
+                long_form.object().required.append(&mut package.object().required);
+                long_form.object().additional_properties = Some(Box::new(false.into()));
+
+                let packages = SchemaObject {
+                    instance_type: Some(InstanceType::Array.into()),
+                    array: Some(Box::new(ArrayValidation {
+                        items: Some(
+                            Schema::Object(SchemaObject {
+                                subschemas: Some(Box::new(SubschemaValidation {
+                                    any_of: Some(vec![
+                                        gen.subschema_for::<String>(),
+                                        long_form.into(),
+                                    ]),
+                                    ..Default::default()
+                                })),
+                                ..Default::default()
+                            })
+                            .into(),
+                        ),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                };
+
+                schema.object().properties.insert(backend_name.clone(), packages.into());
+                package_ids
+                    .object()
+                    .properties
+                    .insert(backend_name, string_array.clone());
+            )*
+        };
+    }
+    apply_public_backends!(x);
+
+    let package_ids = Schema::from(package_ids);
+    let object = schema.object();
+    object
+        .properties
+        .insert("include".to_string(), string_array);
+    object
+        .properties
+        .insert("exclude".to_string(), package_ids.clone());
+    object.properties.insert("ignore".to_string(), package_ids);
+    object
+        .properties
+        .insert("hooks".to_string(), gen.subschema_for::<GroupHooks>());
+    object.additional_properties = Some(Box::new(false.into()));
+
+    RootSchema {
+        meta_schema: gen.settings().meta_schema.clone(),
+        schema,
+        definitions: gen.definitions().clone(),
+    }
+}
+
+/// The keys allowed in group files other than backend names.
+const GROUP_FILE_KEYS: &[&str] = &["include", "exclude", "ignore", "hooks"];
+
+fn parse_group_file(
+    group_file: &Path,
+    contents: &str,
+    variables: &BTreeMap<String, String>,
+) -> Result<GroupFile> {
+    let mut parsed = GroupFile::default();
+
+    let format = GroupFileFormat::from_path(group_file);
+
+    let mut toml = format.parse(contents)?;
+
+    check_keys(group_file, contents, format, &toml)?;
+
+    // hooks are shell commands which use ${...} for their own environment
+    // variables, such as ${METAPAC_PACKAGES}, so they are left unexpanded
+    for (key, value) in toml.iter_mut() {
+        if key != "hooks" {
+            expand_variables(value, variables)?;
+        }
+    }
 
     for (key, value) in toml.iter() {
-        raw_install_options.append(&mut parse_toml_key_value(group_file, key, value)?);
+        if key == "include" {
+            parsed.includes = value.clone().try_into().wrap_err(eyre!(
+                "the include key in the {group_file:?} group file is not an array of group names"
+            ))?;
+        } else if key == "exclude" {
+            parsed.excludes = parse_package_ids_table(group_file, key, value)?;
+        } else if key == "ignore" {
+            parsed.ignored = parse_package_ids_table(group_file, key, value)?;
+        } else if key == "hooks" {
+            parsed.hooks = value.clone().try_into().wrap_err(eyre!(
+                "parsing the hooks table in the {group_file:?} group file"
+            ))?;
+        } else {
+            parsed.raw_install_options.append(&mut parse_toml_key_value(
+                group_file,
+                key,
+                value,
+                &mut parsed.post_install,
+            )?);
+        }
     }
 
-    Ok(raw_install_options)
+    Ok(parsed)
 }
 
-fn parse_toml_key_value(group_file: &Path, key: &str, value: &Value) -> Result<RawInstallOptions> {
+/// Matches `${name}` along with its escaped form `$${name}`.
+static VARIABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\$?)\$\{([A-Za-z0-9_]+)\}").expect("valid regex"));
+
+/// Replaces every `${name}` in the strings of the value with the variable
+/// of the same name, and every `$${name}` with a literal `${name}`, except in
+/// the `post_install` hooks of long-form packages.
+fn expand_variables(value: &mut Value, variables: &BTreeMap<String, String>) -> Result<()> {
+    match value {
+        Value::String(string) => {
+            if let Some(undefined) = VARIABLE_REGEX
+                .captures_iter(string)
+                .filter(|captures| captures[1].is_empty())
+                .map(|captures| captures[2].to_string())
+                .find(|name| !variables.contains_key(name))
+            {
+                return Err(eyre!(
+                    "undefined variable {undefined:?} in {string:?}, variables can be defined in the variables or hostname_variables configs"
+                ));
+            }
+
+            *string = VARIABLE_REGEX
+                .replace_all(string, |captures: &regex::Captures| {
+                    if captures[1].is_empty() {
+                        variables[&captures[2]].clone()
+                    } else {
+                        format!("${{{}}}", &captures[2])
+                    }
+                })
+                .to_string();
+        }
+        Value::Array(array) => {
+            for value in array {
+                expand_variables(value, variables)?;
+            }
+        }
+        Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                if key != "post_install" {
+                    expand_variables(value, variables)?;
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn check_keys(
+    group_file: &Path,
+    contents: &str,
+    format: GroupFileFormat,
+    toml: &Table,
+) -> Result<()> {
+    // only toml group files can be parsed with the spans used for the
+    // locations of unknown keys, so unknown keys in yaml and json group
+    // files are reported without a line and column
+    let im_document;
+    let document_mut;
+    let document = match format {
+        GroupFileFormat::Toml => {
+            im_document = ImDocument::parse(contents)?;
+            im_document.as_table()
+        }
+        GroupFileFormat::Yaml | GroupFileFormat::Json => {
+            document_mut = toml::to_string(toml)?.parse::<DocumentMut>()?;
+            document_mut.as_table()
+        }
+    };
+
+    let mut checker = KeyChecker::new(group_file, contents);
+
+    checker.check_backend_table(document, GROUP_FILE_KEYS, "the group file");
+
+    for key in ["exclude", "ignore"] {
+        if let Some(table) = document.get(key).and_then(Item::as_table_like) {
+            checker.check_backend_table(table, &[], &format!("the {key} table"));
+        }
+    }
+
+    if let Some(table) = document.get("hooks").and_then(Item::as_table_like) {
+        checker.check_table(table, struct_fields::<GroupHooks>(), "the hooks table");
+    }
+
+    for (key, item) in document.iter() {
+        let packages = item
+            .as_array()
+            .into_iter()
+            .flat_map(|x| x.iter().filter_map(|x| x.as_inline_table()))
+            .map(|x| x as &dyn TableLike)
+            .chain(
+                item.as_array_of_tables()
+                    .into_iter()
+                    .flat_map(|x| x.iter())
+                    .map(|x| x as &dyn TableLike),
+            );
+
+        macro_rules! x {
+            ($($backend:ident),*) => {
+                $(
+                    if key.to_lowercase() == $backend.to_string().to_lowercase() {
+                        let known_keys = struct_fields::<StringPackageStruct>()
+                            .iter()
+                            .chain(struct_fields::<<$backend as Backend>::InstallOptions>())
+                            .copied()
+                            .collect::<Vec<_>>();
+
+                        for package in packages {
+                            let package_name = package.get("package").and_then(Item::as_str).unwrap_or_default();
+
+                            checker.check_table(
+                                package,
+                                &known_keys,
+                                &format!("the long-form {package_name:?} package of the {key} backend"),
+                            );
+                        }
+
+                        continue;
+                    }
+                )*
+            };
+        }
+        apply_public_backends!(x);
+    }
+
+    checker.finish()
+}
+
+/// Parses a table of backend names to arrays of package names, such as the
+/// `exclude` and `ignore` tables.
+fn parse_package_ids_table(group_file: &Path, key: &str, value: &Value) -> Result<PackageIds> {
+    let table = value.as_table().ok_or(eyre!(
+        "the {key} key in the {group_file:?} group file has a non-table value"
+    ))?;
+
+    let mut package_ids = PackageIds::default();
+    for (backend_name, packages) in table.iter() {
+        let Some(backend) = AnyBackend::ALL
+            .iter()
+            .find(|backend| backend.to_string().to_lowercase() == backend_name.to_lowercase())
+        else {
+            log::warn!("unrecognised backend: {backend_name:?} in the {key} table in group file: {group_file:?}");
+            continue;
+        };
+
+        let packages: Vec<String> = packages.clone().try_into().wrap_err(eyre!(
+            "the {backend} backend in the {key} table in the {group_file:?} group file is not an array of package names"
+        ))?;
+
+        package_ids.entry(*backend).or_default().extend(packages);
+    }
+
+    Ok(package_ids)
+}
+
+fn parse_toml_key_value(
+    group_file: &Path,
+    key: &str,
+    value: &Value,
+    post_install: &mut BTreeMap<(AnyBackend, String), Vec<String>>,
+) -> Result<RawInstallOptions> {
     macro_rules! x {
         ($($backend:ident),*) => {
             $(
@@ -141,10 +773,18 @@ fn parse_toml_key_value(group_file: &Path, key: &str, value: &Value) -> Result<R
                         let (package_id, package_install_options) =
                             match package {
                                 toml::Value::String(x) => (x.to_string(), Default::default()),
-                                toml::Value::Table(x) => (
-                                    x.clone().try_into::<StringPackageStruct>()?.package,
-                                    x.clone().try_into()?,
-                                ),
+                                toml::Value::Table(x) => {
+                                    let package = x.clone().try_into::<StringPackageStruct>()?;
+
+                                    if let Some(command) = package.post_install {
+                                        post_install
+                                            .entry((AnyBackend::$backend, package.package.clone()))
+                                            .or_default()
+                                            .push(command);
+                                    }
+
+                                    (package.package, x.clone().try_into()?)
+                                }
                                 _ => return Err(eyre!("the {} backend in the {group_file:?} group file has a package which is neither a string or a table", $backend)),
                             };
 
@@ -162,3 +802,55 @@ fn parse_toml_key_value(group_file: &Path, key: &str, value: &Value) -> Result<R
 
     Ok(RawInstallOptions::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_variables_and_escapes() {
+        let variables = BTreeMap::from([("user".to_string(), "alice".to_string())]);
+
+        let mut value = Value::Array(vec![
+            Value::String("/home/${user}/bin".to_string()),
+            Value::String("$${user} stays ${user}".to_string()),
+        ]);
+        expand_variables(&mut value, &variables).unwrap();
+
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::String("/home/alice/bin".to_string()),
+                Value::String("${user} stays alice".to_string()),
+            ])
+        );
+
+        let mut undefined = Value::String("${missing}".to_string());
+        assert!(expand_variables(&mut undefined, &variables).is_err());
+    }
+
+    #[test]
+    fn hooks_are_not_expanded() {
+        let variables = BTreeMap::from([("user".to_string(), "alice".to_string())]);
+
+        let group_file = parse_group_file(
+            Path::new("example.toml"),
+            r#"
+arch = [
+    { package = "neovim", post_install = "echo ${user} ${METAPAC_PACKAGES}" },
+]
+
+[hooks]
+pre_sync = ["echo ${METAPAC_PACKAGES}"]
+"#,
+            &variables,
+        )
+        .unwrap();
+
+        assert_eq!(
+            group_file.post_install[&(AnyBackend::Arch, "neovim".to_string())],
+            ["echo ${user} ${METAPAC_PACKAGES}"]
+        );
+        assert_eq!(group_file.hooks.pre_sync, ["echo ${METAPAC_PACKAGES}"]);
+    }
+}
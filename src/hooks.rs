@@ -0,0 +1,119 @@
+//! Shell commands run before and after packages are installed or removed
+
+use std::process::{Command, Stdio};
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::prelude::*;
+
+/// The hooks from the config which run on every sync or clean.
+#[serde_inline_default]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Hooks {
+    /// commands run before installing missing packages
+    #[serde_inline_default(Hooks::default().pre_sync)]
+    pub pre_sync: Vec<String>,
+    /// commands run after installing missing packages
+    #[serde_inline_default(Hooks::default().post_sync)]
+    pub post_sync: Vec<String>,
+    /// commands run before removing unmanaged packages
+    #[serde_inline_default(Hooks::default().pre_clean)]
+    pub pre_clean: Vec<String>,
+    /// commands run after removing unmanaged packages
+    #[serde_inline_default(Hooks::default().post_clean)]
+    pub post_clean: Vec<String>,
+    /// what to do when any hook fails, including group and package hooks
+    #[serde_inline_default(Hooks::default().on_failure)]
+    pub on_failure: HookFailurePolicy,
+}
+
+/// The hooks from a group file which run when any of its packages are
+/// installed.
+#[serde_inline_default]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GroupHooks {
+    /// commands run before installing any of the group file's packages
+    #[serde_inline_default(GroupHooks::default().pre_sync)]
+    pub pre_sync: Vec<String>,
+    /// commands run after installing any of the group file's packages
+    #[serde_inline_default(GroupHooks::default().post_sync)]
+    pub post_sync: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookFailurePolicy {
+    /// stop at the first failing hook and return an error
+    #[default]
+    Abort,
+    /// log a warning and carry on
+    Warn,
+}
+
+/// Runs each hook command with `sh -c`, passing the affected packages in
+/// the `METAPAC_PACKAGES` environment variable and those of each backend in
+/// `METAPAC_<BACKEND>_PACKAGES`, all space separated.
+pub fn run_hooks(
+    hook: &str,
+    commands: &[String],
+    packages: &PackageIds,
+    config: &Config,
+) -> Result<()> {
+    for command in commands {
+        log::info!("running {hook} hook: {command:?}");
+
+        let result = run_hook(hook, command, packages);
+
+        match (result, config.hooks.on_failure) {
+            (Ok(()), _) => {}
+            (Err(error), HookFailurePolicy::Abort) => return Err(error),
+            (Err(error), HookFailurePolicy::Warn) => log::warn!("{error}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn run_hook(hook: &str, command: &str, packages: &PackageIds) -> Result<()> {
+    let backend_packages = packages.iter().map(|(backend, packages)| {
+        (
+            format!("METAPAC_{}_PACKAGES", backend.to_string().to_uppercase()),
+            packages
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    });
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("METAPAC_HOOK", hook)
+        .env(
+            "METAPAC_PACKAGES",
+            packages
+                .values()
+                .flatten()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+        .envs(backend_packages)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .wrap_err(eyre!("running {hook} hook: {command:?}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(eyre!("{hook} hook failed: {command:?}"))
+    }
+}
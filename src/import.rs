@@ -0,0 +1,227 @@
+//! Importing group files from other package managers
+
+use std::collections::BTreeMap;
+use std::fs::{self, read_to_string};
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Value};
+
+use crate::prelude::*;
+
+/// Converts every pacdef group file in `pacdef_dir` into a metapac group file
+/// of the same name in `group_dir`, skipping any which already exist.
+pub fn import_pacdef(pacdef_dir: &Path, group_dir: &Path) -> Result<()> {
+    if !pacdef_dir.is_dir() {
+        return Err(eyre!(
+            "the pacdef groups directory {pacdef_dir:?} was not found"
+        ));
+    }
+
+    let mut untranslated = Vec::new();
+
+    for entry in walkdir::WalkDir::new(pacdef_dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|x| !x.file_type().is_dir())
+    {
+        let pacdef_file = entry.path();
+        let group_file = group_dir
+            .join(pacdef_file.strip_prefix(pacdef_dir)?)
+            .with_extension("toml");
+
+        if group_file.exists() {
+            untranslated.push(format!(
+                "{pacdef_file:?}: not imported as the group file {group_file:?} already exists"
+            ));
+            continue;
+        }
+
+        let contents = read_to_string(pacdef_file)
+            .wrap_err(eyre!("reading pacdef group file {pacdef_file:?}"))?;
+
+        let doc = parse_pacdef_group_file(pacdef_file, &contents, &mut untranslated);
+
+        if let Some(parent) = group_file.parent() {
+            fs::create_dir_all(parent).wrap_err(eyre!("creating directory {parent:?}"))?;
+        }
+        fs::write(&group_file, doc.to_string())
+            .wrap_err(eyre!("writing group file {group_file:?}"))?;
+
+        log::info!("imported pacdef group file {pacdef_file:?} as {group_file:?}");
+    }
+
+    for message in untranslated.iter() {
+        log::warn!("{message}");
+    }
+
+    Ok(())
+}
+
+/// The metapac backend for a pacdef section name, pacdef used the name of the
+/// distribution or language rather than the package manager for some backends.
+fn pacdef_backend(section: &str) -> Option<AnyBackend> {
+    match section {
+        "arch" => Some(AnyBackend::Arch),
+        "debian" => Some(AnyBackend::Apt),
+        "fedora" => Some(AnyBackend::Dnf),
+        "flatpak" => Some(AnyBackend::Flatpak),
+        "python" => Some(AnyBackend::Pipx),
+        "rust" => Some(AnyBackend::Cargo),
+        "rustup" => Some(AnyBackend::Rustup),
+        "void" => Some(AnyBackend::Xbps),
+        _ => None,
+    }
+}
+
+fn parse_pacdef_group_file(
+    pacdef_file: &Path,
+    contents: &str,
+    untranslated: &mut Vec<String>,
+) -> DocumentMut {
+    let mut packages: BTreeMap<AnyBackend, Vec<String>> = BTreeMap::new();
+    // pacdef declares rustup components on separate lines to their toolchains
+    let mut rustup_components: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    let mut section: Option<Option<AnyBackend>> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            let backend = pacdef_backend(name.trim());
+            if backend.is_none() {
+                untranslated.push(format!(
+                    "{pacdef_file:?}:{line_number}: unrecognised pacdef section [{name}], skipping its packages"
+                ));
+            }
+            section = Some(backend);
+            continue;
+        }
+
+        match section {
+            None => untranslated.push(format!(
+                "{pacdef_file:?}:{line_number}: package {line:?} is not in any section"
+            )),
+            Some(None) => {}
+            Some(Some(AnyBackend::Rustup)) => {
+                let parts = line.split('/').collect::<Vec<_>>();
+                match parts.as_slice() {
+                    ["toolchain", toolchain] => {
+                        rustup_components.entry(toolchain.to_string()).or_default();
+                    }
+                    ["component", toolchain, component] => {
+                        rustup_components
+                            .entry(toolchain.to_string())
+                            .or_default()
+                            .push(component.to_string());
+                    }
+                    _ => untranslated.push(format!(
+                        "{pacdef_file:?}:{line_number}: unrecognised rustup package {line:?}"
+                    )),
+                }
+            }
+            Some(Some(backend)) => {
+                packages.entry(backend).or_default().push(line.to_string());
+            }
+        }
+    }
+
+    let mut doc = DocumentMut::new();
+
+    for (backend, packages) in packages {
+        doc.insert(
+            &backend.to_string().to_lowercase(),
+            multiline_array(packages.into_iter().map(Value::from)),
+        );
+    }
+
+    if !rustup_components.is_empty() {
+        doc.insert(
+            &AnyBackend::Rustup.to_string().to_lowercase(),
+            multiline_array(
+                rustup_components
+                    .into_iter()
+                    .map(|(toolchain, components)| {
+                        if components.is_empty() {
+                            Value::from(toolchain)
+                        } else {
+                            let mut table = InlineTable::new();
+                            table.insert("package", Value::from(toolchain));
+                            table.insert("components", Value::Array(Array::from_iter(components)));
+                            Value::InlineTable(table)
+                        }
+                    }),
+            ),
+        );
+    }
+
+    doc
+}
+
+/// An array formatted with one value per line, like the example group file.
+fn multiline_array(values: impl IntoIterator<Item = Value>) -> Item {
+    let mut array = values
+        .into_iter()
+        .map(|mut value| {
+            value.decor_mut().set_prefix("\n\t");
+            value
+        })
+        .collect::<Array>();
+
+    array.set_trailing("\n");
+    array.set_trailing_comma(true);
+
+    Item::Value(Value::Array(array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pacdef_group_file_sections() {
+        let contents = "\
+[arch]
+base-devel # comment
+neovim
+
+[rustup]
+toolchain/stable
+component/nightly/rust-src
+component/nightly/clippy
+nightly/rustfmt
+
+[unknown]
+skipped
+";
+
+        let mut untranslated = Vec::new();
+        let doc = parse_pacdef_group_file(Path::new("base"), contents, &mut untranslated);
+
+        assert_eq!(
+            doc.to_string(),
+            "\
+arch = [
+\t\"base-devel\",
+\t\"neovim\",
+]
+rustup = [
+\t{ package = \"nightly\", components = [\"rust-src\", \"clippy\"] },
+\t\"stable\",
+]
+"
+        );
+
+        assert_eq!(untranslated.len(), 2);
+        assert!(untranslated[0].contains("unrecognised rustup package \"nightly/rustfmt\""));
+        assert!(untranslated[1].contains("unrecognised pacdef section [unknown]"));
+    }
+}
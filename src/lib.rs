@@ -5,8 +5,12 @@ mod cli;
 mod cmd;
 mod config;
 mod core;
+mod facts;
 mod groups;
+mod hooks;
+mod import;
 mod prelude;
 mod review;
+mod unknown_keys;
 
 pub use prelude::*;
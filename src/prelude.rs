@@ -1,24 +1,49 @@
 pub use crate::backends::all::{
-    AnyBackend, InstallOptions, PackageIds, QueryInfos, RawInstallOptions, RawPackageIds,
+    AnyBackend, BackendConfigs, InstallOptions, PackageIds, QueryInfos, RawInstallOptions,
+    RawPackageIds,
 };
+pub use crate::backends::apk::{Apk, ApkConfig, ApkInstallOptions, ApkQueryInfo};
 pub(crate) use crate::backends::apply_public_backends;
-pub use crate::backends::apt::{Apt, AptQueryInfo};
-pub use crate::backends::arch::{Arch, ArchInstallOptions, ArchQueryInfo};
-pub use crate::backends::cargo::Cargo;
-pub use crate::backends::dnf::{Dnf, DnfInstallOptions, DnfQueryInfo};
-pub use crate::backends::flatpak::{Flatpak, FlatpakQueryInfo};
-pub use crate::backends::pipx::Pipx;
-pub use crate::backends::rustup::{Rustup, RustupInstallOptions, RustupQueryInfo};
-pub use crate::backends::xbps::Xbps;
+pub use crate::backends::apt::{Apt, AptConfig, AptQueryInfo};
+pub use crate::backends::arch::{
+    Arch, ArchConfig, ArchInstallOptions, ArchPackageManager, ArchQueryInfo,
+};
+pub use crate::backends::brew::{Brew, BrewConfig, BrewInstallOptions, BrewQueryInfo};
+pub use crate::backends::cargo::{Cargo, CargoConfig};
+pub use crate::backends::custom::{
+    Custom, CustomBackendDefinition, CustomConfig, CustomInstallOptions, CustomQueryInfo,
+};
+pub use crate::backends::dnf::{Dnf, DnfConfig, DnfInstallOptions, DnfQueryInfo};
+pub use crate::backends::flatpak::{Flatpak, FlatpakConfig, FlatpakQueryInfo};
+pub use crate::backends::gem::{Gem, GemConfig, GemInstallOptions, GemQueryInfo};
+pub use crate::backends::go::{Go, GoConfig, GoInstallOptions, GoQueryInfo};
+pub use crate::backends::nix::{Nix, NixConfig, NixInstallOptions, NixQueryInfo};
+pub use crate::backends::npm::{
+    Npm, NpmConfig, NpmInstallOptions, NpmPackageManager, NpmQueryInfo,
+};
+pub use crate::backends::pipx::{Pipx, PipxConfig};
+pub use crate::backends::rustup::{Rustup, RustupConfig, RustupInstallOptions, RustupQueryInfo};
+pub use crate::backends::snap::{Snap, SnapConfig, SnapInstallOptions, SnapQueryInfo};
+pub use crate::backends::uv::{Uv, UvConfig, UvInstallOptions, UvQueryInfo};
+pub use crate::backends::xbps::{Xbps, XbpsConfig};
+pub use crate::backends::zypper::{Zypper, ZypperConfig, ZypperInstallOptions, ZypperQueryInfo};
 pub use crate::backends::Backend;
 pub use crate::backends::StringPackageStruct;
+pub use crate::backends::{merge_lists, merge_optionals, MergeInstallOptions};
 pub use crate::cli::AddCommand;
 pub use crate::cli::CleanCommand;
+pub use crate::cli::ImportCommand;
+pub use crate::cli::ImportPacdefCommand;
+pub use crate::cli::ImportSource;
 pub use crate::cli::MainArguments;
 pub use crate::cli::MainSubcommand;
 pub use crate::cli::ReviewCommand;
+pub use crate::cli::SchemaCommand;
+pub use crate::cli::SchemaFile;
 pub use crate::cli::SyncCommand;
 pub use crate::cli::UnmanagedCommand;
 pub use crate::cmd::Perms;
-pub use crate::config::{ArchPackageManager, Config};
-pub use crate::groups::Groups;
+pub use crate::config::{Config, GroupConditions};
+pub use crate::facts::Facts;
+pub use crate::groups::{group_file_path, group_file_schema, GroupFileFormat, Groups};
+pub use crate::hooks::{GroupHooks, HookFailurePolicy, Hooks};
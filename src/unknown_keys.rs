@@ -0,0 +1,152 @@
+//! Detection of unknown keys in config and group files
+
+use std::path::Path;
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use itertools::Itertools;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use toml_edit::TableLike;
+
+use crate::prelude::*;
+
+/// The field names of a struct which derives [`Deserialize`].
+pub fn struct_fields<'de, T>() -> &'static [&'static str]
+where
+    T: Deserialize<'de>,
+{
+    struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldsDeserializer<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V>(self, _: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            *self.0 = fields;
+            Err(de::Error::custom("only collecting field names"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields
+}
+
+/// Collects the unknown keys in the tables of a single toml file.
+pub struct KeyChecker<'a> {
+    file: &'a Path,
+    contents: &'a str,
+    unknown_keys: Vec<String>,
+}
+
+impl<'a> KeyChecker<'a> {
+    pub fn new(file: &'a Path, contents: &'a str) -> Self {
+        KeyChecker {
+            file,
+            contents,
+            unknown_keys: Vec::new(),
+        }
+    }
+
+    /// Checks that every key in the table is one of the `known_keys`.
+    pub fn check_table(&mut self, table: &dyn TableLike, known_keys: &[&str], context: &str) {
+        for (key, _) in table.iter() {
+            if !known_keys.contains(&key) {
+                self.unknown_key(table, key, known_keys, context);
+            }
+        }
+    }
+
+    /// Checks that every key in the table is either a backend name, matched
+    /// case-insensitively, or one of the `extra_keys`.
+    pub fn check_backend_table(
+        &mut self,
+        table: &dyn TableLike,
+        extra_keys: &[&str],
+        context: &str,
+    ) {
+        let backend_names = AnyBackend::ALL
+            .iter()
+            .map(|backend| backend.to_string().to_lowercase())
+            .collect::<Vec<_>>();
+        let known_keys = backend_names
+            .iter()
+            .map(String::as_str)
+            .chain(extra_keys.iter().copied())
+            .collect::<Vec<_>>();
+
+        for (key, _) in table.iter() {
+            if !extra_keys.contains(&key) && !backend_names.contains(&key.to_lowercase()) {
+                self.unknown_key(table, key, &known_keys, context);
+            }
+        }
+    }
+
+    fn unknown_key(
+        &mut self,
+        table: &dyn TableLike,
+        key: &str,
+        known_keys: &[&str],
+        context: &str,
+    ) {
+        let location = table
+            .key(key)
+            .and_then(|key| key.span())
+            .map(|span| {
+                let (line, column) = line_column(self.contents, span.start);
+                format!("{}:{line}:{column}", self.file.display())
+            })
+            .unwrap_or_else(|| self.file.display().to_string());
+
+        let suggestion = known_keys
+            .iter()
+            .map(|known_key| (strsim::levenshtein(key, known_key), known_key))
+            .filter(|(distance, known_key)| *distance <= known_key.len().max(3) / 3)
+            .min()
+            .map(|(_, known_key)| format!(", did you mean {known_key:?}?"))
+            .unwrap_or_default();
+
+        self.unknown_keys.push(format!(
+            "unknown key {key:?} in {context} at {location}{suggestion}"
+        ));
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if self.unknown_keys.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre!("{}", self.unknown_keys.iter().join("\n")))
+        }
+    }
+}
+
+/// The one-based line and column of a byte offset in a string.
+fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
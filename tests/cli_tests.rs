@@ -7,3 +7,111 @@ fn unmanaged() {
     cmd.args(["--hostname", "pc", "--config-dir", ".", "unmanaged"]);
     cmd.assert().success();
 }
+
+#[test]
+fn unmanaged_hostname_pattern() {
+    for (hostname, matches) in [("ci-runner-01", true), ("laptop", false)] {
+        let mut cmd = Command::cargo_bin("metapac").unwrap();
+        cmd.args([
+            "--hostname",
+            hostname,
+            "--config-dir",
+            "tests/configs/hostname_glob",
+            "unmanaged",
+        ]);
+
+        let output = cmd.output().unwrap();
+        assert_eq!(output.status.success(), matches);
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr).contains("no hostname entry"),
+            !matches
+        );
+    }
+}
+
+#[test]
+fn unknown_config_key() {
+    let mut cmd = Command::cargo_bin("metapac").unwrap();
+    cmd.args([
+        "--hostname",
+        "pc",
+        "--config-dir",
+        "tests/configs/unknown_key",
+        "unmanaged",
+    ]);
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("did you mean"));
+}
+
+#[test]
+fn schema() {
+    for file in ["config", "group"] {
+        let mut cmd = Command::cargo_bin("metapac").unwrap();
+        cmd.args(["schema", file]);
+        cmd.assert().success();
+    }
+}
+
+#[test]
+fn hostname_regex_is_anchored() {
+    for (hostname, matches) in [("lap", true), ("mylaptop", false)] {
+        let mut cmd = Command::cargo_bin("metapac").unwrap();
+        cmd.args([
+            "--hostname",
+            hostname,
+            "--config-dir",
+            "tests/configs/hostname_regex",
+            "unmanaged",
+        ]);
+
+        let output = cmd.output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr).contains("no hostname entry"),
+            !matches
+        );
+    }
+}
+
+#[test]
+fn escaped_variable() {
+    let mut cmd = Command::cargo_bin("metapac").unwrap();
+    cmd.args([
+        "--hostname",
+        "pc",
+        "--config-dir",
+        "tests/configs/escaped_variable",
+        "unmanaged",
+    ]);
+    cmd.assert().success();
+}
+
+#[test]
+fn empty_custom_command() {
+    let mut cmd = Command::cargo_bin("metapac").unwrap();
+    cmd.args([
+        "--hostname",
+        "pc",
+        "--config-dir",
+        "tests/configs/empty_custom_command",
+        "unmanaged",
+    ]);
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("custom_backends.opam.install"));
+}
+
+#[test]
+fn hook_variables() {
+    let mut cmd = Command::cargo_bin("metapac").unwrap();
+    cmd.args([
+        "--hostname",
+        "pc",
+        "--config-dir",
+        "tests/configs/hook_variables",
+        "unmanaged",
+    ]);
+    cmd.assert().success();
+}